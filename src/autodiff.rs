@@ -0,0 +1,258 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::{Arc, Mutex};
+use crate::enums::BuiltinFunction;
+use crate::expression::{Expression, ExpressionArgs, ExpressionFn, ExpressionSettings};
+use crate::matrix::Matrix;
+use crate::number::Number;
+
+/// Dual number carrying the partial derivatives of its value, for forward-mode differentiation.
+/// Missing trailing gradient entries are zero, so constants have an empty gradient.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Dual {
+    value: f64,
+    gradient: Vec<f64>,
+}
+
+/// Value recorded on a tape for reverse-mode differentiation. Constants are not recorded.
+#[derive(Clone, Debug)]
+pub struct Tracked {
+    value: f64,
+    node: Option<usize>,
+    tape: Option<Arc<Mutex<Tape>>>,
+}
+
+/// Every tape entry holds the partial derivatives with respect to up to two earlier entries.
+type Tape = Vec<[(usize, f64); 2]>;
+
+pub(crate) fn derivative(function: BuiltinFunction, value: f64) -> f64 {
+    match function {
+        BuiltinFunction::Sqrt => 0.5 / value.sqrt(),
+        BuiltinFunction::Exp => value.exp(),
+        BuiltinFunction::Ln => 1.0 / value,
+        BuiltinFunction::Sin => value.cos(),
+        BuiltinFunction::Cos => -value.sin(),
+        BuiltinFunction::Tan => 1.0 / (value.cos() * value.cos()),
+        BuiltinFunction::Abs if value == 0.0 => 0.0,
+        BuiltinFunction::Abs => value.signum(),
+    }
+}
+
+/// Partial derivatives of `base^exponent`. The exponent's one is only needed when the exponent
+/// varies, which keeps negative bases with constant exponents free of NaN.
+pub(crate) fn power_partials(base: f64, exponent: f64, result: f64, exponent_varies: bool) -> (f64, f64) {
+    let base_partial = if exponent == 0.0 { 0.0 } else { exponent * base.powf(exponent - 1.0) };
+    let exponent_partial = match exponent_varies {
+        false => 0.0,
+        true if result == 0.0 => 0.0,
+        true => result * base.ln(),
+    };
+    (base_partial, exponent_partial)
+}
+
+impl Dual {
+    pub fn constant(value: f64) -> Dual {
+        Dual { value, gradient: Vec::new() }
+    }
+
+    /// The `index`-th of `count` variables being differentiated against.
+    pub fn variable(value: f64, index: usize, count: usize) -> Dual {
+        let mut gradient = vec![0.0; count];
+        gradient[index] = 1.0;
+        Dual { value, gradient }
+    }
+
+    pub fn value(&self) -> f64 { self.value }
+
+    pub fn gradient(&self) -> &[f64] { &self.gradient }
+
+    fn combine(&self, self_partial: f64, other: &Dual, other_partial: f64, value: f64) -> Dual {
+        let gradient = (0..self.gradient.len().max(other.gradient.len()))
+            .map(|i| self.gradient.get(i).unwrap_or(&0.0) * self_partial + other.gradient.get(i).unwrap_or(&0.0) * other_partial)
+            .collect();
+        Dual { value, gradient }
+    }
+
+    fn chain(&self, partial: f64, value: f64) -> Dual {
+        Dual { value, gradient: self.gradient.iter().map(|entry| entry * partial).collect() }
+    }
+}
+
+impl Display for Dual {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} (gradient {:?})", self.value, self.gradient)
+    }
+}
+
+impl Number for Dual {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> { Ok(Dual::constant(literal.parse::<f64>()?)) }
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(1.0, other, 1.0, self.value + other.value))
+    }
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(1.0, other, -1.0, self.value - other.value))
+    }
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(other.value, other, self.value, self.value * other.value))
+    }
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value / other.value;
+        Ok(self.combine(1.0 / other.value, other, -value / other.value, value))
+    }
+    fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value.powf(exponent.value);
+        let exponent_varies = exponent.gradient.iter().any(|entry| *entry != 0.0);
+        let (base_partial, exponent_partial) = power_partials(self.value, exponent.value, value, exponent_varies);
+        Ok(self.combine(base_partial, exponent, exponent_partial, value))
+    }
+    fn apply_function(&self, function: BuiltinFunction, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.chain(derivative(function, self.value), self.value.apply_function(function, settings)?))
+    }
+    fn compare(&self, other: &Self, settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> { self.value.compare(&other.value, settings) }
+    fn is_zero(&self, delta: f64) -> bool { self.value.abs() <= delta }
+    fn to_index(&self) -> Option<usize> { self.value.to_index() }
+}
+
+impl Tracked {
+    pub fn constant(value: f64) -> Tracked {
+        Tracked { value, node: None, tape: None }
+    }
+
+    pub fn value(&self) -> f64 { self.value }
+
+    /// Records a value computed from `parents`, each paired with the partial derivative with respect to it.
+    fn derived(value: f64, parents: [(&Tracked, f64); 2]) -> Tracked {
+        let tape = match parents.iter().find_map(|(parent, _)| parent.tape.as_ref()) {
+            None => return Tracked::constant(value),
+            Some(tape) => tape.clone(),
+        };
+        let entry = parents.map(|(parent, partial)| match parent.node {
+            None => (0, 0.0),
+            Some(node) => (node, partial),
+        });
+        let mut entries = tape.lock().unwrap();
+        entries.push(entry);
+        let node = entries.len() - 1;
+        drop(entries);
+        Tracked { value, node: Some(node), tape: Some(tape) }
+    }
+}
+
+impl PartialEq for Tracked {
+    fn eq(&self, other: &Self) -> bool { self.value == other.value }
+}
+
+impl Display for Tracked {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.value)
+    }
+}
+
+impl Number for Tracked {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> { Ok(Tracked::constant(literal.parse::<f64>()?)) }
+    fn to_index(&self) -> Option<usize> { self.value.to_index() }
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(Tracked::derived(self.value + other.value, [(self, 1.0), (other, 1.0)]))
+    }
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(Tracked::derived(self.value - other.value, [(self, 1.0), (other, -1.0)]))
+    }
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(Tracked::derived(self.value * other.value, [(self, other.value), (other, self.value)]))
+    }
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value / other.value;
+        Ok(Tracked::derived(value, [(self, 1.0 / other.value), (other, -value / other.value)]))
+    }
+    fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value.powf(exponent.value);
+        let (base_partial, exponent_partial) = power_partials(self.value, exponent.value, value, exponent.node.is_some());
+        Ok(Tracked::derived(value, [(self, base_partial), (exponent, exponent_partial)]))
+    }
+    fn apply_function(&self, function: BuiltinFunction, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value.apply_function(function, settings)?;
+        Ok(Tracked::derived(value, [(self, derivative(function, self.value)), (self, 0.0)]))
+    }
+    fn compare(&self, other: &Self, settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> { self.value.compare(&other.value, settings) }
+    fn is_zero(&self, delta: f64) -> bool { self.value.abs() <= delta }
+}
+
+/// Step of the central difference estimating the slope of a function from `ExpressionArgs::functions`.
+const SLOPE_STEP: f64 = 1e-6;
+
+/// Value and estimated slope of a function passed as a closure, whose derivative is not known.
+fn estimate_slope(function: &ExpressionFn, value: f64) -> (f64, f64) {
+    let step = SLOPE_STEP * value.abs().max(1.0);
+    (function(value), (function(value + step) - function(value - step)) / (2.0 * step))
+}
+
+/// Copies everything but the variables from `args` for evaluation with another number type,
+/// with numbers as constants of that type.
+fn constant_args<M: Number>(args: &ExpressionArgs, constant: fn(f64) -> M, function: impl Fn(ExpressionFn) -> ExpressionFn<M>) -> Result<ExpressionArgs<M>, Box<dyn Error>> {
+    let mut converted = ExpressionArgs::empty();
+    converted.settings = args.settings.clone();
+    converted.definitions = args.definitions.convert()?;
+    converted.strings = args.strings.clone();
+    for (name, value) in &args.functions {
+        converted.functions.insert(name.clone(), function(value.clone()));
+    }
+    for (name, value) in &args.variables {
+        converted.variables.insert(name.clone(), constant(*value));
+    }
+    for (name, values) in &args.lists {
+        converted.lists.insert(name.clone(), values.iter().map(|value| constant(*value)).collect());
+    }
+    for (name, matrix) in &args.matrices {
+        converted.matrices.insert(name.clone(), Matrix::new(matrix.rows(), matrix.columns(), matrix.values().iter().map(|value| constant(*value)).collect())?);
+    }
+    Ok(converted)
+}
+
+/// Evaluates the expression and its gradient with respect to `variables` in one forward pass.
+/// Other variables, lists and matrices in `args` are treated as constants. The derivatives of
+/// functions from `ExpressionArgs::functions` are estimated with a central difference.
+pub fn forward_gradient(exp: &dyn Expression<Dual>, args: &ExpressionArgs, variables: &[&str]) -> Result<(f64, Vec<f64>), Box<dyn Error>> {
+    let values = args.values(variables)?;
+    let mut dual_args = constant_args(args, Dual::constant, |function| Arc::new(move |x: Dual| {
+        let (value, slope) = estimate_slope(&function, x.value);
+        x.chain(slope, value)
+    }))?;
+    for (index, (name, value)) in variables.iter().zip(values).enumerate() {
+        dual_args.variables.insert(name.to_string(), Dual::variable(value, index, variables.len()));
+    }
+
+    let result = exp.evaluate(&dual_args)?;
+    let mut gradient = result.gradient;
+    gradient.resize(variables.len(), 0.0);
+    Ok((result.value, gradient))
+}
+
+/// Same as `forward_gradient`, but records the evaluation on a tape and propagates derivatives
+/// backwards, so the cost does not grow with the number of variables.
+pub fn reverse_gradient(exp: &dyn Expression<Tracked>, args: &ExpressionArgs, variables: &[&str]) -> Result<(f64, Vec<f64>), Box<dyn Error>> {
+    let values = args.values(variables)?;
+    let tape = Arc::new(Mutex::new(Tape::new()));
+    let mut tracked_args = constant_args(args, Tracked::constant, |function| Arc::new(move |x: Tracked| {
+        let (value, slope) = estimate_slope(&function, x.value);
+        Tracked::derived(value, [(&x, slope), (&x, 0.0)])
+    }))?;
+    for (index, (name, value)) in variables.iter().zip(values).enumerate() {
+        tape.lock().unwrap().push([(index, 0.0); 2]);
+        tracked_args.variables.insert(name.to_string(), Tracked { value, node: Some(index), tape: Some(tape.clone()) });
+    }
+
+    let result = exp.evaluate(&tracked_args)?;
+    let entries = tape.lock().unwrap();
+    let mut adjoints = vec![0.0; entries.len()];
+    if let Some(output) = result.node {
+        adjoints[output] = 1.0;
+        for node in (0..=output).rev() {
+            for (parent, partial) in entries[node] {
+                adjoints[parent] += adjoints[node] * partial;
+            }
+        }
+    }
+    adjoints.truncate(variables.len());
+    Ok((result.value, adjoints))
+}
@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::thread;
+use crate::errors::{into_send, ColumnLength};
+use crate::expression::{Expression, ExpressionSettings};
+use crate::number::Number;
+
+/// Number of rows evaluated per pass over the expression tree.
+pub const BATCH_SIZE: usize = 1024;
+
+pub struct BatchArgs<'a, N = f64> {
+    pub columns: HashMap<String, &'a [N]>,
+    pub settings: ExpressionSettings,
+}
+
+/// Buffers for the operands of intermediate results, reused from one batch to the next. The
+/// tree is walked in the same order for every batch, so each node gets the same buffers back.
+pub struct BatchScratch<N = f64> {
+    buffers: Vec<Vec<N>>,
+}
+
+impl<N: Number> BatchScratch<N> {
+    pub fn new() -> Self {
+        Self { buffers: Vec::new() }
+    }
+
+    /// A buffer as long as `rows`, whose contents are to be overwritten.
+    pub(crate) fn take(&mut self, rows: &[N]) -> Vec<N> {
+        let mut buffer = self.buffers.pop().unwrap_or_default();
+        buffer.truncate(rows.len());
+        let filled = buffer.len();
+        buffer.extend_from_slice(&rows[filled..]);
+        buffer
+    }
+
+    pub(crate) fn give_back(&mut self, buffer: Vec<N>) {
+        self.buffers.push(buffer);
+    }
+}
+
+impl<N: Number> Default for BatchScratch<N> {
+    fn default() -> Self { Self::new() }
+}
+
+impl<'a, N: Number> BatchArgs<'a, N> {
+    pub fn empty() -> Self {
+        Self {
+            columns: HashMap::new(),
+            settings: ExpressionSettings::default(),
+        }
+    }
+}
+
+/// Evaluates the expression for every row, writing one result per row into `output`.
+/// Every column must have exactly `output.len()` rows.
+pub fn evaluate_batch<N: Number>(exp: &dyn Expression<N>, args: &BatchArgs<N>, output: &mut [N]) -> Result<(), Box<dyn Error>> {
+    check_columns(args, output.len())?;
+    evaluate_rows(exp, args, 0, output)
+}
+
+/// Same as `evaluate_batch`, but splits the rows between up to `threads` worker threads.
+pub fn evaluate_batch_parallel<N: Number>(exp: &dyn Expression<N>, args: &BatchArgs<N>, output: &mut [N], threads: usize) -> Result<(), Box<dyn Error>> {
+    check_columns(args, output.len())?;
+    let batches = output.len().div_ceil(BATCH_SIZE);
+    let rows_per_thread = batches.div_ceil(threads.max(1)).max(1) * BATCH_SIZE;
+
+    // `Box<dyn Error>` cannot leave a worker thread, so workers convert their errors to ones that can
+    let results: Vec<Result<(), Box<dyn Error + Send + Sync>>> = thread::scope(|scope| {
+        let handles: Vec<_> = output.chunks_mut(rows_per_thread)
+            .enumerate()
+            .map(|(index, rows)| scope.spawn(move || evaluate_rows(exp, args, index * rows_per_thread, rows).map_err(into_send)))
+            .collect();
+        handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+    });
+    match results.into_iter().find_map(Result::err) {
+        None => Ok(()),
+        Some(error) => Err(error),
+    }
+}
+
+fn check_columns<N: Number>(args: &BatchArgs<N>, rows: usize) -> Result<(), Box<dyn Error>> {
+    for (name, column) in &args.columns {
+        if column.len() != rows {
+            return Err(Box::from(ColumnLength { name: name.clone(), expected: rows, actual: column.len() }));
+        }
+    }
+    Ok(())
+}
+
+fn evaluate_rows<N: Number>(exp: &dyn Expression<N>, args: &BatchArgs<N>, offset: usize, output: &mut [N]) -> Result<(), Box<dyn Error>> {
+    let mut scratch = BatchScratch::new();
+    for (index, chunk) in output.chunks_mut(BATCH_SIZE).enumerate() {
+        exp.evaluate_batch(args, offset + index * BATCH_SIZE, chunk, &mut scratch)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,122 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use crate::enums::ExpressionType;
+use crate::errors::IterationLimit;
+use crate::expression::{attach_to_operand, Expression, ExpressionSettings, Scope};
+use crate::number::Number;
+use crate::value::Value;
+
+/// Local definition, written `let name = value in body`. The value, a number, list or matrix, is
+/// evaluated once, and the name shadows a variable of the same name in `ExpressionArgs` within the body.
+#[derive(Clone)]
+pub struct Let<N: Number = f64> {
+    pub name: String,
+    pub value: Box<dyn Expression<N>>,
+    pub body: Box<dyn Expression<N>>,
+}
+
+/// Sum of `body` for every whole step of `variable` from `lower` to `upper`, written
+/// `sum(i, 1, n, body)` or `Σ(i, 1, n, body)`. An empty range sums to zero. A `sum` whose first
+/// of four arguments is not a bare name is the aggregate over its arguments.
+#[derive(Clone)]
+pub struct Summation<N: Number = f64> {
+    pub variable: String,
+    pub lower: Box<dyn Expression<N>>,
+    pub upper: Box<dyn Expression<N>>,
+    pub body: Box<dyn Expression<N>>,
+}
+
+/// Product of `body` over a range, written `prod(i, 1, n, body)` or `Π(i, 1, n, body)`.
+/// An empty range gives one.
+#[derive(Clone)]
+pub struct Product<N: Number = f64> {
+    pub variable: String,
+    pub lower: Box<dyn Expression<N>>,
+    pub upper: Box<dyn Expression<N>>,
+    pub body: Box<dyn Expression<N>>,
+}
+
+impl<N: Number> Let<N> {
+    fn scope<'s>(&self, args: &'s Scope<N>) -> Result<Scope<'s, N>, Box<dyn Error>> {
+        args.scope(&self.name, self.value.evaluate_value(args)?)
+    }
+}
+
+impl<N: Number> Expression<N> for Let<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        self.value.can_evaluate(args) && self.scope(args).is_ok_and(|scope| self.body.can_evaluate(&scope))
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.body.evaluate(&self.scope(args)?) }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.body.evaluate_value(&self.scope(args)?) }
+    fn to_string(&self) -> String { format!("let {} = {} in {}", self.name, self.value, self.body) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Let }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.value.as_ref(), self.body.as_ref()] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+/// Combines the values of `body` with `operation`, starting from `identity`, while the bound
+/// variable steps by one from `lower` up to `upper`.
+fn fold_range<N: Number>(
+    variable: &str,
+    bounds: [&dyn Expression<N>; 2],
+    body: &dyn Expression<N>,
+    args: &Scope<N>,
+    identity: &str,
+    operation: impl Fn(&N, &N, &ExpressionSettings) -> Result<N, Box<dyn Error>>,
+) -> Result<N, Box<dyn Error>> {
+    let upper = bounds[1].evaluate(args)?;
+    let step = N::parse_literal("1")?;
+    let mut index = bounds[0].evaluate(args)?;
+    let mut result = N::parse_literal(identity)?;
+    let mut iterations = 0;
+    while index.compare(&upper, &args.settings)? != Ordering::Greater {
+        iterations += 1;
+        if iterations > args.settings.max_iterations {
+            return Err(Box::from(IterationLimit { limit: args.settings.max_iterations }));
+        }
+        let scope = args.scope(variable, Value::Number(index.clone()))?;
+        result = operation(&result, &body.evaluate(&scope)?, &args.settings)?;
+        index = index.add(&step, &args.settings)?;
+    }
+    Ok(result)
+}
+
+/// The body can be evaluated if it can with the index variable bound to the lower bound.
+fn range_can_evaluate<N: Number>(variable: &str, bounds: [&dyn Expression<N>; 2], body: &dyn Expression<N>, args: &Scope<N>) -> bool {
+    bounds.iter().all(|bound| bound.can_evaluate(args))
+        && bounds[0].evaluate(args).is_ok_and(|lower| args.scope(variable, Value::Number(lower)).is_ok_and(|scope| body.can_evaluate(&scope)))
+}
+
+impl<N: Number> Expression<N> for Summation<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        range_can_evaluate(&self.variable, [self.lower.as_ref(), self.upper.as_ref()], self.body.as_ref(), args)
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        fold_range(&self.variable, [self.lower.as_ref(), self.upper.as_ref()], self.body.as_ref(), args, "0", N::add)
+    }
+    fn to_string(&self) -> String { format!("Σ({}, {}, {}, {})", self.variable, self.lower, self.upper, self.body) }
+    fn bound_variable(&self) -> Option<&str> { Some(&self.variable) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Summation }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.lower.as_ref(), self.upper.as_ref(), self.body.as_ref()] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Expression<N> for Product<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        range_can_evaluate(&self.variable, [self.lower.as_ref(), self.upper.as_ref()], self.body.as_ref(), args)
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        fold_range(&self.variable, [self.lower.as_ref(), self.upper.as_ref()], self.body.as_ref(), args, "1", N::multiply)
+    }
+    fn to_string(&self) -> String { format!("Π({}, {}, {}, {})", self.variable, self.lower, self.upper, self.body) }
+    fn bound_variable(&self) -> Option<&str> { Some(&self.variable) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Product }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.lower.as_ref(), self.upper.as_ref(), self.body.as_ref()] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
@@ -0,0 +1,113 @@
+use std::error::Error;
+use crate::enums::BuiltinFunction;
+use crate::errors::{SlotCount, UnknownVariable};
+use crate::expression::{Expression, ExpressionSettings, Scope};
+use crate::number::Number;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Instruction<N = f64> {
+    Push(N),
+    Load(usize),
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+    Remainder,
+    Power,
+    PlusMinus,
+    Convert,
+    Call(BuiltinFunction),
+}
+
+pub struct Compiler<N = f64> {
+    instructions: Vec<Instruction<N>>,
+    variables: Vec<String>,
+    fixed_variables: bool,
+    depth: usize,
+    stack_size: usize,
+}
+
+#[derive(Clone, Debug)]
+pub struct Program<N = f64> {
+    instructions: Vec<Instruction<N>>,
+    variables: Vec<String>,
+    stack_size: usize,
+}
+
+impl<N: Number> Compiler<N> {
+    pub fn emit(&mut self, instruction: Instruction<N>) {
+        match instruction {
+            Instruction::Push(_) | Instruction::Load(_) => self.depth += 1,
+            Instruction::Add | Instruction::Subtract | Instruction::Multiply | Instruction::Divide | Instruction::Remainder | Instruction::Power | Instruction::PlusMinus | Instruction::Convert => self.depth -= 1,
+            Instruction::Call(_) => {}
+        }
+        self.stack_size = self.stack_size.max(self.depth);
+        self.instructions.push(instruction);
+    }
+
+    pub fn variable_slot(&mut self, name: &str) -> Result<usize, Box<dyn Error>> {
+        match self.variables.iter().position(|variable| variable == name) {
+            Some(slot) => Ok(slot),
+            None if self.fixed_variables => Err(Box::from(UnknownVariable { name: name.to_string() })),
+            None => {
+                self.variables.push(name.to_string());
+                Ok(self.variables.len() - 1)
+            }
+        }
+    }
+}
+
+impl<N: Number> Program<N> {
+    /// Compiles the expression, assigning slots to variables in order of their first appearance.
+    pub fn compile(exp: &dyn Expression<N>) -> Result<Program<N>, Box<dyn Error>> {
+        Program::compile_with(exp, Vec::new(), false)
+    }
+
+    /// Compiles the expression using the given slot order. Unlisted variables are an error.
+    pub fn compile_with_variables(exp: &dyn Expression<N>, variables: &[&str]) -> Result<Program<N>, Box<dyn Error>> {
+        Program::compile_with(exp, variables.iter().map(|name| name.to_string()).collect(), true)
+    }
+
+    fn compile_with(exp: &dyn Expression<N>, variables: Vec<String>, fixed_variables: bool) -> Result<Program<N>, Box<dyn Error>> {
+        let mut compiler = Compiler { instructions: Vec::new(), variables, fixed_variables, depth: 0, stack_size: 0 };
+        exp.compile(&mut compiler)?;
+        Ok(Program { instructions: compiler.instructions, variables: compiler.variables, stack_size: compiler.stack_size })
+    }
+
+    pub fn instructions(&self) -> &[Instruction<N>] { &self.instructions }
+
+    pub fn variables(&self) -> &[String] { &self.variables }
+
+    /// Looks up variable values once, producing slots suitable for `evaluate`.
+    pub fn bind(&self, args: &Scope<N>) -> Result<Vec<N>, Box<dyn Error>> {
+        args.values(&self.variables)
+    }
+
+    pub fn evaluate(&self, slots: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+        self.evaluate_with_stack(slots, settings, &mut Vec::with_capacity(self.stack_size))
+    }
+
+    /// Same as `evaluate`, but reuses the given stack to avoid an allocation per call.
+    pub fn evaluate_with_stack(&self, slots: &[N], settings: &ExpressionSettings, stack: &mut Vec<N>) -> Result<N, Box<dyn Error>> {
+        if slots.len() < self.variables.len() {
+            return Err(Box::from(SlotCount { expected: self.variables.len(), actual: slots.len() }));
+        }
+        stack.clear();
+        for instruction in &self.instructions {
+            match instruction {
+                Instruction::Push(value) => stack.push(value.clone()),
+                Instruction::Load(slot) => stack.push(slots[*slot].clone()),
+                Instruction::Add => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.add(&right, settings)? }
+                Instruction::Subtract => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.subtract(&right, settings)? }
+                Instruction::Multiply => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.multiply(&right, settings)? }
+                Instruction::Divide => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.divide(&right, settings)? }
+                Instruction::Remainder => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.remainder(&right, settings)? }
+                Instruction::Power => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.power(&right, settings)? }
+                Instruction::PlusMinus => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.with_uncertainty(&right, settings)? }
+                Instruction::Convert => { let right = stack.pop().unwrap(); let left = stack.last_mut().unwrap(); *left = left.convert(&right, settings)? }
+                Instruction::Call(function) => { let top = stack.last_mut().unwrap(); *top = top.apply_function(*function, settings)? }
+            }
+        }
+        Ok(stack.pop().unwrap())
+    }
+}
@@ -0,0 +1,12 @@
+use std::error::Error;
+use crate::expression::{Expression, ExpressionSettings};
+use crate::number::Number;
+
+/// Compiled expression, called with the variable values and the settings to evaluate with.
+pub type CompiledFn<N = f64> = Box<dyn Fn(&[N], &ExpressionSettings) -> Result<N, Box<dyn Error>> + Send + Sync>;
+
+/// Composes the expression into a closure taking variable values in the order of `variables`.
+/// Use `ExpressionArgs::values` with the same ordering to build the input slice.
+pub fn compile<N: Number>(exp: &dyn Expression<N>, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+    exp.to_closure(variables)
+}
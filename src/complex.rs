@@ -0,0 +1,143 @@
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+use crate::enums::BuiltinFunction;
+use crate::errors::ParsingError;
+use crate::expression::ExpressionSettings;
+use crate::number::Number;
+
+/// Complex number; `i` and literals with an `i` suffix such as `4i` are imaginary.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Complex {
+    pub real: f64,
+    pub imaginary: f64,
+}
+
+impl Complex {
+    pub fn new(real: f64, imaginary: f64) -> Complex {
+        Complex { real, imaginary }
+    }
+
+    fn from_polar(modulus: f64, argument: f64) -> Complex {
+        Complex::new(modulus * argument.cos(), modulus * argument.sin())
+    }
+
+    pub fn modulus(&self) -> f64 { self.real.hypot(self.imaginary) }
+
+    pub fn argument(&self) -> f64 { self.imaginary.atan2(self.real) }
+
+    fn is_real(&self) -> bool { self.imaginary == 0.0 }
+
+    fn multiply(&self, other: &Complex) -> Complex {
+        Complex::new(self.real * other.real - self.imaginary * other.imaginary, self.real * other.imaginary + self.imaginary * other.real)
+    }
+
+    fn divide(&self, other: &Complex) -> Complex {
+        let denominator = other.real * other.real + other.imaginary * other.imaginary;
+        Complex::new(
+            (self.real * other.real + self.imaginary * other.imaginary) / denominator,
+            (self.imaginary * other.real - self.real * other.imaginary) / denominator,
+        )
+    }
+
+    pub fn sqrt(&self) -> Complex {
+        let modulus = self.modulus();
+        let real = ((modulus + self.real) / 2.0).sqrt();
+        let imaginary = ((modulus - self.real) / 2.0).sqrt();
+        Complex::new(real, if self.imaginary < 0.0 { -imaginary } else { imaginary })
+    }
+
+    pub fn exp(&self) -> Complex { Complex::from_polar(self.real.exp(), self.imaginary) }
+
+    /// Principal logarithm, with the imaginary part in (-pi, pi].
+    pub fn ln(&self) -> Complex { Complex::new(self.modulus().ln(), self.argument()) }
+
+    pub fn sin(&self) -> Complex {
+        Complex::new(self.real.sin() * self.imaginary.cosh(), self.real.cos() * self.imaginary.sinh())
+    }
+
+    pub fn cos(&self) -> Complex {
+        Complex::new(self.real.cos() * self.imaginary.cosh(), -self.real.sin() * self.imaginary.sinh())
+    }
+
+    pub fn tan(&self) -> Complex { self.sin().divide(&self.cos()) }
+
+    pub fn powc(&self, exponent: &Complex) -> Complex {
+        if self.is_real() && exponent.is_real() && (self.real >= 0.0 || exponent.real.fract() == 0.0) {
+            return Complex::new(self.real.powf(exponent.real), 0.0);
+        }
+        if self.real == 0.0 && self.imaginary == 0.0 {
+            return Complex::new(0.0, 0.0);
+        }
+        // for a negative real base, ln gives an argument of exactly pi
+        let logarithm = if self.is_real() && self.real < 0.0 { Complex::new((-self.real).ln(), PI) } else { self.ln() };
+        exponent.multiply(&logarithm).exp()
+    }
+}
+
+impl Display for Complex {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.imaginary == 0.0 {
+            write!(f, "{}", self.real)
+        } else if self.real == 0.0 {
+            write!(f, "{}i", self.imaginary)
+        } else if self.imaginary < 0.0 {
+            write!(f, "{} - {}i", self.real, -self.imaginary)
+        } else {
+            write!(f, "{} + {}i", self.real, self.imaginary)
+        }
+    }
+}
+
+impl Number for Complex {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+        match literal.strip_suffix('i') {
+            None => Ok(Complex::new(literal.parse::<f64>()?, 0.0)),
+            Some("") => Ok(Complex::new(0.0, 1.0)),
+            Some("-") => Ok(Complex::new(0.0, -1.0)),
+            Some(coefficient) if coefficient.ends_with('i') => Err(Box::from(ParsingError { message: "Invalid imaginary literal" })),
+            Some(coefficient) => Ok(Complex::new(0.0, coefficient.parse::<f64>()?)),
+        }
+    }
+
+    fn parse_constant(name: &str) -> Option<Self> {
+        match name {
+            "i" => Some(Complex::new(0.0, 1.0)),
+            _ => None,
+        }
+    }
+
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(Complex::new(self.real + other.real, self.imaginary + other.imaginary))
+    }
+
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(Complex::new(self.real - other.real, self.imaginary - other.imaginary))
+    }
+
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(Complex::multiply(self, other)) }
+
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(Complex::divide(self, other)) }
+
+    fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(self.powc(exponent)) }
+
+    fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(match function {
+            BuiltinFunction::Sqrt => self.sqrt(),
+            BuiltinFunction::Exp => self.exp(),
+            BuiltinFunction::Ln => self.ln(),
+            BuiltinFunction::Sin => self.sin(),
+            BuiltinFunction::Cos => self.cos(),
+            BuiltinFunction::Tan => self.tan(),
+            BuiltinFunction::Abs => Complex::new(self.modulus(), 0.0),
+        })
+    }
+
+    fn is_zero(&self, delta: f64) -> bool { self.modulus() <= delta }
+    fn to_index(&self) -> Option<usize> {
+        match self.imaginary == 0.0 {
+            true => self.real.to_index(),
+            false => None,
+        }
+    }
+}
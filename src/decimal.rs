@@ -0,0 +1,207 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::enums::{BuiltinFunction, InexactDivision, RoundingMode};
+use crate::errors::{ArithmeticError, ParsingError, UnsupportedOperation};
+use crate::expression::ExpressionSettings;
+use crate::number::Number;
+
+const OVERFLOW: ArithmeticError = ArithmeticError { message: "Decimal overflow" };
+
+/// Fixed-point decimal worth `units / 10^scale`, with up to 38 significant digits.
+/// Arithmetic results are rounded to `ExpressionSettings::decimal_scale`.
+#[derive(Clone, Copy, Debug)]
+pub struct Decimal {
+    units: i128,
+    scale: u32,
+}
+
+fn power_of_ten(exponent: u32) -> Result<i128, Box<dyn Error>> {
+    Ok(10i128.checked_pow(exponent).ok_or(OVERFLOW)?)
+}
+
+/// Divides with the given rounding mode, returning the quotient and whether it is exact.
+fn divide_rounded(numerator: i128, denominator: i128, rounding_mode: RoundingMode) -> (i128, bool) {
+    let quotient = numerator / denominator;
+    let remainder = numerator % denominator;
+    if remainder == 0 {
+        return (quotient, true);
+    }
+
+    let sign = if (numerator < 0) != (denominator < 0) { -1 } else { 1 };
+    let remainder = remainder.unsigned_abs();
+    let rest = denominator.unsigned_abs() - remainder;
+    let away_from_zero = match rounding_mode {
+        RoundingMode::Up => true,
+        RoundingMode::Down => false,
+        RoundingMode::Ceiling => sign > 0,
+        RoundingMode::Floor => sign < 0,
+        RoundingMode::HalfUp => remainder >= rest,
+        RoundingMode::HalfDown => remainder > rest,
+        RoundingMode::HalfEven => remainder > rest || (remainder == rest && quotient % 2 != 0),
+    };
+    (if away_from_zero { quotient + sign } else { quotient }, false)
+}
+
+impl Decimal {
+    pub fn new(units: i128, scale: u32) -> Decimal {
+        Decimal { units, scale }
+    }
+
+    pub fn units(&self) -> i128 { self.units }
+
+    pub fn scale(&self) -> u32 { self.scale }
+
+    pub fn to_f64(&self) -> f64 { self.units as f64 / 10f64.powi(self.scale as i32) }
+
+    pub fn rescale(&self, scale: u32, rounding_mode: RoundingMode) -> Result<Decimal, Box<dyn Error>> {
+        let units = if scale >= self.scale {
+            self.units.checked_mul(power_of_ten(scale - self.scale)?).ok_or(OVERFLOW)?
+        } else {
+            divide_rounded(self.units, power_of_ten(self.scale - scale)?, rounding_mode).0
+        };
+        Ok(Decimal { units, scale })
+    }
+
+    fn aligned_units(&self, other: &Decimal) -> Result<(i128, i128, u32), Box<dyn Error>> {
+        let scale = self.scale.max(other.scale);
+        Ok((
+            self.units.checked_mul(power_of_ten(scale - self.scale)?).ok_or(OVERFLOW)?,
+            other.units.checked_mul(power_of_ten(scale - other.scale)?).ok_or(OVERFLOW)?,
+            scale,
+        ))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Self) -> bool {
+        match self.aligned_units(other) {
+            Ok((left, right, _)) => left == right,
+            Err(_) => false,
+        }
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let digits = format!("{:0>width$}", self.units.unsigned_abs(), width = self.scale as usize + 1);
+        let split = digits.len() - self.scale as usize;
+        let sign = if self.units < 0 { "-" } else { "" };
+        match self.scale {
+            0 => write!(f, "{}{}", sign, digits),
+            _ => write!(f, "{}{}.{}", sign, &digits[..split], &digits[split..]),
+        }
+    }
+}
+
+impl Number for Decimal {
+    /// Reads the literal exactly, keeping as many fractional digits as it has.
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+        let (negative, digits) = match literal.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, literal),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(Box::from(ParsingError { message: "Invalid decimal literal" }));
+        }
+
+        let mut units: i128 = 0;
+        for digit in whole.chars().chain(fraction.chars()) {
+            units = units.checked_mul(10).and_then(|n| n.checked_add(digit as i128 - '0' as i128)).ok_or(OVERFLOW)?;
+        }
+        Ok(Decimal { units: if negative { -units } else { units }, scale: fraction.len() as u32 })
+    }
+
+    fn add(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let (left, right, scale) = self.aligned_units(other)?;
+        Decimal::new(left.checked_add(right).ok_or(OVERFLOW)?, scale).rescale(settings.decimal_scale, settings.rounding_mode)
+    }
+
+    fn subtract(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let (left, right, scale) = self.aligned_units(other)?;
+        Decimal::new(left.checked_sub(right).ok_or(OVERFLOW)?, scale).rescale(settings.decimal_scale, settings.rounding_mode)
+    }
+
+    fn multiply(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let units = self.units.checked_mul(other.units).ok_or(OVERFLOW)?;
+        Decimal::new(units, self.scale + other.scale).rescale(settings.decimal_scale, settings.rounding_mode)
+    }
+
+    fn divide(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if other.units == 0 {
+            return Err(Box::from(ArithmeticError { message: "Division by zero" }));
+        }
+        // units / 10^scale = (self.units * 10^(scale + other.scale - self.scale)) / other.units
+        let scale = settings.decimal_scale;
+        let (numerator, denominator) = if scale + other.scale >= self.scale {
+            (self.units.checked_mul(power_of_ten(scale + other.scale - self.scale)?).ok_or(OVERFLOW)?, other.units)
+        } else {
+            (self.units, other.units.checked_mul(power_of_ten(self.scale - scale - other.scale)?).ok_or(OVERFLOW)?)
+        };
+        let (units, exact) = divide_rounded(numerator, denominator, settings.rounding_mode);
+        if !exact && settings.inexact_division == InexactDivision::Error {
+            return Err(Box::from(ArithmeticError { message: "Decimal division result does not fit the scale" }));
+        }
+        Ok(Decimal { units, scale })
+    }
+
+    /// Only integer exponents are supported; every intermediate product is rounded to the scale.
+    fn power(&self, exponent: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let divisor = power_of_ten(exponent.scale)?;
+        if exponent.units % divisor != 0 {
+            return Err(Box::from(ArithmeticError { message: "Decimal exponent must be an integer" }));
+        }
+        let mut base = *self;
+        let mut remaining = (exponent.units / divisor).unsigned_abs();
+        let mut result = Decimal::new(1, 0);
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.multiply(&base, settings)?;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                base = base.multiply(&base, settings)?;
+            }
+        }
+        if exponent.units < 0 {
+            return Decimal::new(1, 0).divide(&result, settings);
+        }
+        result.rescale(settings.decimal_scale, settings.rounding_mode)
+    }
+
+    fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        match function {
+            BuiltinFunction::Abs => Ok(Decimal { units: self.units.checked_abs().ok_or(OVERFLOW)?, scale: self.scale }),
+            _ => Err(Box::from(UnsupportedOperation { name: function.get_name() })),
+        }
+    }
+
+    /// Compares the whole parts first and then the fractional parts at a common scale, which
+    /// neither rounds nor overflows.
+    fn compare(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+        let (left_unit, right_unit) = (power_of_ten(self.scale)?, power_of_ten(other.scale)?);
+        let (left_whole, right_whole) = (self.units / left_unit, other.units / right_unit);
+        if left_whole != right_whole {
+            return Ok(left_whole.cmp(&right_whole));
+        }
+        let scale = self.scale.max(other.scale);
+        // a fraction is below 10^scale in magnitude once aligned, so it fits whenever 10^scale does
+        let left_fraction = self.units % left_unit * power_of_ten(scale - self.scale)?;
+        let right_fraction = other.units % right_unit * power_of_ten(scale - other.scale)?;
+        Ok(left_fraction.cmp(&right_fraction))
+    }
+
+    fn is_equal(&self, other: &Self, settings: &ExpressionSettings) -> Result<bool, Box<dyn Error>> {
+        Ok(self.compare(other, settings)? == Ordering::Equal)
+    }
+
+    fn is_zero(&self, _delta: f64) -> bool { self.units == 0 }
+    fn to_index(&self) -> Option<usize> {
+        let unit = power_of_ten(self.scale).ok()?;
+        match self.units % unit {
+            0 => usize::try_from(self.units / unit).ok(),
+            _ => None,
+        }
+    }
+}
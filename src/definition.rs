@@ -0,0 +1,205 @@
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use crate::enums::{Aggregate, BuiltinFunction, MatrixFunction, TextFunction};
+use crate::errors::{ArgumentCount, ParsingError, RecursionCycle, RecursionLimit, UnknownFunction};
+use crate::expression::{Expression, Scope};
+use crate::number::Number;
+use crate::parser::{is_name, is_reserved, parse_at};
+use crate::value::Value;
+
+/// Function written in the expression language, such as `f(x, y) = x^2 + y`.
+#[derive(Clone)]
+pub struct Definition<N: Number = f64> {
+    pub name: String,
+    pub parameters: Vec<String>,
+    pub body: Box<dyn Expression<N>>,
+}
+
+/// Functions defined in the expression language, callable from any expression evaluated with
+/// them in `ExpressionArgs::definitions`.
+///
+/// A function may call itself, up to `ExpressionSettings::max_call_depth` nested calls, but
+/// functions calling each other in a cycle are rejected when they are defined.
+#[derive(Clone)]
+pub struct FunctionRegistry<N: Number = f64> {
+    definitions: HashMap<String, Arc<Definition<N>>>,
+}
+
+impl<N: Number> Display for Definition<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}({}) = {}", self.name, self.parameters.join(", "), self.body)
+    }
+}
+
+/// Position of the `=` separating a definition's head from its body, skipping `==`, `<=`, `>=` and `!=`.
+fn find_assignment(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    (0..bytes.len()).find(|&index| {
+        bytes[index] == b'='
+            && (index == 0 || !b"<>!=".contains(&bytes[index - 1]))
+            && bytes.get(index + 1) != Some(&b'=')
+    })
+}
+
+impl<N: Number> Definition<N> {
+    /// Parses a definition written as `name(parameters) = body`.
+    pub fn parse(text: &str) -> Result<Definition<N>, Box<dyn Error>> {
+        let invalid = || ParsingError { message: "Invalid function definition" };
+        let assignment = find_assignment(text).ok_or_else(invalid)?;
+        let head = text[..assignment].trim();
+        let (name, parameters) = head.strip_suffix(')').and_then(|head| head.split_once('(')).ok_or_else(invalid)?;
+        let name = name.trim();
+        if !is_name(name) {
+            return Err(Box::from(invalid()));
+        }
+        if BuiltinFunction::parse_builtin_function(name).is_some() || Aggregate::parse_aggregate(name).is_some() || MatrixFunction::parse_matrix_function(name).is_some()
+            || TextFunction::parse_text_function(name).is_some() {
+            return Err(Box::from(ParsingError { message: "Built-in functions cannot be redefined" }));
+        }
+        if is_reserved(name) {
+            return Err(Box::from(ParsingError { message: "Reserved words cannot name a function" }));
+        }
+        let parameters: Vec<String> = match parameters.trim() {
+            "" => Vec::new(),
+            parameters => parameters.split(',').map(|parameter| parameter.trim().to_string()).collect(),
+        };
+        if !parameters.iter().all(|parameter| is_name(parameter)) {
+            return Err(Box::from(ParsingError { message: "Invalid parameter name" }));
+        }
+        if parameters.iter().enumerate().any(|(index, parameter)| parameters[..index].contains(parameter)) {
+            return Err(Box::from(ParsingError { message: "Duplicate parameter name" }));
+        }
+        let body = parse_at::<N>(&text[assignment + 1..], text[..=assignment].chars().count())?;
+        Ok(Definition { name: name.to_string(), parameters, body })
+    }
+}
+
+/// Appends the name and argument count of every function called in the expression.
+fn collect_calls<'a, N: Number>(exp: &'a dyn Expression<N>, calls: &mut Vec<(&'a str, usize)>) {
+    if let Some(name) = exp.function_name() {
+        calls.push((name, exp.children().len()));
+    }
+    for child in exp.children() {
+        collect_calls(child, calls);
+    }
+}
+
+impl<N: Number> Default for FunctionRegistry<N> {
+    fn default() -> Self {
+        FunctionRegistry::new()
+    }
+}
+
+impl<N: Number> FunctionRegistry<N> {
+    pub fn new() -> Self {
+        FunctionRegistry { definitions: HashMap::new() }
+    }
+
+    /// Adds the definitions, one per line, replacing existing ones of the same name. Definitions
+    /// may refer to each other regardless of order, and call functions from `ExpressionArgs::functions`,
+    /// which are only looked up when the call is evaluated. Nothing is added if any of them is
+    /// invalid, calls a built-in or defined function with the wrong number of arguments, or is part of a cycle.
+    pub fn define(&mut self, definitions: &str) -> Result<(), Box<dyn Error>> {
+        let mut registry = self.clone();
+        let mut defined = Vec::new();
+        for line in definitions.lines().filter(|line| !line.trim().is_empty()) {
+            let definition = Definition::parse(line)?;
+            if defined.contains(&definition.name) {
+                return Err(Box::from(ParsingError { message: "Function defined twice" }));
+            }
+            defined.push(definition.name.clone());
+            registry.definitions.insert(definition.name.clone(), Arc::new(definition));
+        }
+        registry.check_calls()?;
+        registry.check_cycles()?;
+        *self = registry;
+        Ok(())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Definition<N>> {
+        self.definitions.get(name).map(|definition| definition.as_ref())
+    }
+
+    fn check_calls(&self) -> Result<(), Box<dyn Error>> {
+        for definition in self.definitions.values() {
+            let mut calls = Vec::new();
+            collect_calls(definition.body.as_ref(), &mut calls);
+            for (name, count) in calls {
+                let expected = match (BuiltinFunction::parse_builtin_function(name), self.definitions.get(name)) {
+                    (Some(_), _) => 1,
+                    (None, Some(callee)) => callee.parameters.len(),
+                    // functions from `ExpressionArgs::functions` are looked up when the call is evaluated
+                    (None, None) => continue,
+                };
+                if count != expected {
+                    return Err(Box::from(ArgumentCount { name: name.to_string(), expected, actual: count }));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Rejects functions that call each other in a cycle; a function calling itself is allowed.
+    fn check_cycles(&self) -> Result<(), Box<dyn Error>> {
+        let mut names: Vec<&String> = self.definitions.keys().collect();
+        names.sort();
+        let mut finished = Vec::new();
+        for name in names {
+            self.visit(name, &mut Vec::new(), &mut finished)?;
+        }
+        Ok(())
+    }
+
+    fn visit<'a>(&'a self, name: &'a str, path: &mut Vec<&'a str>, finished: &mut Vec<&'a str>) -> Result<(), Box<dyn Error>> {
+        if finished.contains(&name) {
+            return Ok(());
+        }
+        if let Some(start) = path.iter().position(|visited| *visited == name) {
+            let mut functions: Vec<String> = path[start..].iter().map(|function| function.to_string()).collect();
+            functions.push(name.to_string());
+            return Err(Box::from(RecursionCycle { functions }));
+        }
+        let Some(definition) = self.definitions.get(name) else { return Ok(()) };
+        let mut calls = Vec::new();
+        collect_calls(definition.body.as_ref(), &mut calls);
+        path.push(name);
+        for (callee, _) in calls {
+            if callee != name {
+                self.visit(callee, path, finished)?;
+            }
+        }
+        path.pop();
+        finished.push(name);
+        Ok(())
+    }
+
+    /// Evaluates the body of the named function with its parameters bound to `values`. The body
+    /// sees only its parameters, not the caller's variables.
+    pub(crate) fn call(&self, name: &str, values: Vec<N>, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let definition = self.definitions.get(name).ok_or_else(|| UnknownFunction { name: name.to_string() })?;
+        if values.len() != definition.parameters.len() {
+            return Err(Box::from(ArgumentCount { name: name.to_string(), expected: definition.parameters.len(), actual: values.len() }));
+        }
+        if args.depth >= args.settings.max_call_depth {
+            return Err(Box::from(RecursionLimit { name: name.to_string(), limit: args.settings.max_call_depth }));
+        }
+        let mut scope = args.root().clone();
+        scope.variables = definition.parameters.iter().cloned().zip(values).collect();
+        scope.lists.clear();
+        scope.matrices.clear();
+        scope.strings.clear();
+        scope.settings = args.settings.clone();
+        scope.depth = args.depth + 1;
+        definition.body.evaluate_value(&scope)
+    }
+
+    /// The same definitions for another number type, parsed again from their text.
+    pub(crate) fn convert<M: Number>(&self) -> Result<FunctionRegistry<M>, Box<dyn Error>> {
+        let definitions = self.definitions.iter()
+            .map(|(name, definition)| Ok((name.clone(), Arc::new(Definition::parse(&definition.to_string())?))))
+            .collect::<Result<_, Box<dyn Error>>>()?;
+        Ok(FunctionRegistry { definitions })
+    }
+}
@@ -0,0 +1,123 @@
+use std::error::Error;
+use crate::enums::{BuiltinFunction, ExpressionType};
+use crate::expression::{Addition, Bracket, Division, Expression, ExpressionArgs, Function, Multiplication, Power, ScalarValue, Subtraction};
+use crate::number::Number;
+
+// Builders for derivative trees. They drop zero terms and unit factors, fold operations on two
+// literals, and bracket operands so that the printed tree parses back to the same expression.
+
+pub(crate) fn literal<N: Number>(text: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    Ok(Box::from(ScalarValue { value: N::parse_literal(text)? }))
+}
+
+fn is_literal<N: Number>(exp: &dyn Expression<N>, text: &str) -> bool {
+    matches!(exp.get_exp_type(), ExpressionType::ScalarValue) && N::parse_literal(text).is_ok_and(|value| exp.to_string() == value.to_literal())
+}
+
+/// Brackets an operand that binds looser than its operator, or as tight on the side that does not
+/// associate: the right of `+`, `-`, `*` and `/`, and the left of `^`.
+fn operand<N: Number>(exp: Box<dyn Expression<N>>, operator: ExpressionType, right: bool) -> Box<dyn Expression<N>> {
+    let (own, outer) = (exp.get_exp_type().precedence(), operator.precedence());
+    match own < outer || (own == outer && right != matches!(operator, ExpressionType::Power)) {
+        true => Box::from(Bracket { inner: exp }),
+        false => exp,
+    }
+}
+
+fn fold<N: Number>(exp: Box<dyn Expression<N>>, left: &dyn Expression<N>, right: &dyn Expression<N>) -> Box<dyn Expression<N>> {
+    let literals = matches!((left.get_exp_type(), right.get_exp_type()), (ExpressionType::ScalarValue, ExpressionType::ScalarValue));
+    match literals.then(|| exp.evaluate(&ExpressionArgs::empty())) {
+        Some(Ok(value)) => Box::from(ScalarValue { value }),
+        _ => exp,
+    }
+}
+
+pub(crate) fn sum<N: Number>(left: Box<dyn Expression<N>>, right: Box<dyn Expression<N>>) -> Box<dyn Expression<N>> {
+    if is_literal(left.as_ref(), "0") {
+        return right;
+    }
+    if is_literal(right.as_ref(), "0") {
+        return left;
+    }
+    let exp = Box::from(Addition { left: operand(left.clone(), ExpressionType::Addition, false), right: Some(operand(right.clone(), ExpressionType::Addition, true)), span: None });
+    fold(exp, left.as_ref(), right.as_ref())
+}
+
+pub(crate) fn difference<N: Number>(left: Box<dyn Expression<N>>, right: Box<dyn Expression<N>>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    if is_literal(right.as_ref(), "0") {
+        return Ok(left);
+    }
+    if is_literal(left.as_ref(), "0") {
+        return Ok(product(literal("-1")?, right));
+    }
+    let exp = Box::from(Subtraction { left: operand(left.clone(), ExpressionType::Subtraction, false), right: Some(operand(right.clone(), ExpressionType::Subtraction, true)), span: None });
+    Ok(fold(exp, left.as_ref(), right.as_ref()))
+}
+
+pub(crate) fn product<N: Number>(left: Box<dyn Expression<N>>, right: Box<dyn Expression<N>>) -> Box<dyn Expression<N>> {
+    if is_literal(left.as_ref(), "0") || is_literal(right.as_ref(), "1") {
+        return left;
+    }
+    if is_literal(right.as_ref(), "0") || is_literal(left.as_ref(), "1") {
+        return right;
+    }
+    let exp = Box::from(Multiplication { left: operand(left.clone(), ExpressionType::Multiplication, false), right: Some(operand(right.clone(), ExpressionType::Multiplication, true)), span: None });
+    fold(exp, left.as_ref(), right.as_ref())
+}
+
+pub(crate) fn quotient<N: Number>(left: Box<dyn Expression<N>>, right: Box<dyn Expression<N>>) -> Box<dyn Expression<N>> {
+    if is_literal(left.as_ref(), "0") || is_literal(right.as_ref(), "1") {
+        return left;
+    }
+    let exp = Box::from(Division { left: operand(left.clone(), ExpressionType::Division, false), right: Some(operand(right.clone(), ExpressionType::Division, true)), span: None });
+    fold(exp, left.as_ref(), right.as_ref())
+}
+
+pub(crate) fn power<N: Number>(left: Box<dyn Expression<N>>, right: Box<dyn Expression<N>>) -> Box<dyn Expression<N>> {
+    if is_literal(right.as_ref(), "1") {
+        return left;
+    }
+    let exp = Box::from(Power { left: operand(left.clone(), ExpressionType::Power, false), right: Some(operand(right.clone(), ExpressionType::Power, true)), span: None });
+    fold(exp, left.as_ref(), right.as_ref())
+}
+
+/// Derivative of `left * right`, by the product rule.
+pub(crate) fn product_rule<N: Number>(left: &dyn Expression<N>, right: &dyn Expression<N>, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    Ok(sum(product(left.differentiate(variable)?, right.clone_box()), product(left.clone_box(), right.differentiate(variable)?)))
+}
+
+fn call<N: Number>(function: BuiltinFunction, argument: &dyn Expression<N>) -> Box<dyn Expression<N>> {
+    Box::from(Function { name: function.get_name().to_string(), arguments: vec![argument.clone_box()] })
+}
+
+/// Derivative of `base^exponent`. A constant exponent gives the power rule, which also holds for
+/// negative bases; otherwise `d(u^v) = u^v * (v' ln(u) + v u' / u)`.
+pub(crate) fn power_derivative<N: Number>(base: &dyn Expression<N>, exponent: &dyn Expression<N>, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let (base_slope, exponent_slope) = (base.differentiate(variable)?, exponent.differentiate(variable)?);
+    if is_literal(exponent_slope.as_ref(), "0") {
+        let lowered = power(base.clone_box(), difference(exponent.clone_box(), literal("1")?)?);
+        return Ok(product(product(exponent.clone_box(), lowered), base_slope));
+    }
+    let logarithm = product(exponent_slope, call(BuiltinFunction::Ln, base));
+    let ratio = quotient(product(exponent.clone_box(), base_slope), base.clone_box());
+    Ok(product(power(base.clone_box(), exponent.clone_box()), sum(logarithm, ratio)))
+}
+
+/// Derivative of a built-in function of `argument`, by the chain rule. That of `abs` is `u / abs(u)`,
+/// which is undefined at zero.
+pub(crate) fn function_derivative<N: Number>(function: BuiltinFunction, argument: &dyn Expression<N>, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let slope = argument.differentiate(variable)?;
+    if is_literal(slope.as_ref(), "0") {
+        return Ok(slope);
+    }
+    let outer = match function {
+        BuiltinFunction::Sqrt => quotient(literal("1")?, product(literal("2")?, call(function, argument))),
+        BuiltinFunction::Exp => call(function, argument),
+        BuiltinFunction::Ln => quotient(literal("1")?, argument.clone_box()),
+        BuiltinFunction::Sin => call(BuiltinFunction::Cos, argument),
+        BuiltinFunction::Cos => product(literal("-1")?, call(BuiltinFunction::Sin, argument)),
+        BuiltinFunction::Tan => quotient(literal("1")?, power(call(BuiltinFunction::Cos, argument), literal("2")?)),
+        BuiltinFunction::Abs => quotient(argument.clone_box(), call(function, argument)),
+    };
+    Ok(product(outer, slope))
+}
@@ -0,0 +1,50 @@
+use std::fmt::Write;
+use crate::expression::{Expression, Scope};
+use crate::number::Number;
+
+impl<N: Number> dyn Expression<N> {
+    pub fn to_dot(&self) -> String {
+        render_dot(self, None)
+    }
+
+    pub fn to_dot_with_values(&self, args: &Scope<N>) -> String {
+        render_dot(self, Some(args))
+    }
+}
+
+fn render_dot<N: Number>(exp: &dyn Expression<N>, args: Option<&Scope<N>>) -> String {
+    let mut output = String::from("digraph expression {\n    ordering=out;\n");
+    let mut next_id = 0;
+    write_node(exp, args, &mut next_id, &mut output);
+    output.push_str("}\n");
+    output
+}
+
+fn write_node<N: Number>(exp: &dyn Expression<N>, args: Option<&Scope<N>>, next_id: &mut usize, output: &mut String) -> usize {
+    let id = *next_id;
+    *next_id += 1;
+
+    let children = exp.children();
+    let mut label = format!("{:?}", exp.get_exp_type());
+    if children.is_empty() {
+        label = format!("{}\\n{}", label, escape_label(&exp.to_string()));
+    }
+    if let Some(args) = args {
+        let value = match exp.evaluate_value(args) {
+            Ok(value) => value.to_string(),
+            Err(e) => e.to_string(),
+        };
+        label = format!("{}\\n= {}", label, escape_label(&value));
+    }
+    writeln!(output, "    n{} [label=\"{}\"];", id, label).unwrap();
+
+    for child in children {
+        let child_id = write_node(child, args, next_id, output);
+        writeln!(output, "    n{} -> n{};", id, child_id).unwrap();
+    }
+    id
+}
+
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
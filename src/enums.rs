@@ -4,6 +4,12 @@ pub enum BufferState {
     Number,
     Name,
     Bracket,
+    /// A comparison operator that may be followed by `=`.
+    Operator,
+    /// Inside a quoted string literal.
+    Text,
+    /// Right after a backslash inside a string literal.
+    Escape,
 }
 
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
@@ -14,6 +20,7 @@ pub enum CharType {
     Whitespace,
     Bracket,
     Point,
+    Quote,
     Unknown,
 }
 
@@ -23,41 +30,320 @@ pub enum OperatorType {
     Subtract,
     Multiply,
     Divide,
+    Modulo,
     Power,
+    PlusMinus,
+    /// A value directly followed by a constant, as in `5 m`.
+    ImplicitMultiply,
+    /// The `to` keyword.
+    Convert,
+    Compare(ComparisonType),
+    /// The `and` keyword.
+    And,
+    /// The `or` keyword.
+    Or,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ComparisonType {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+    NotEqual,
 }
 
 #[derive(Debug)]
 pub enum ExpressionType {
     ScalarValue,
+    Constant,
+    Variable,
+    Bracket,
+    Function,
     Addition,
     Subtraction,
     Multiplication,
-    Division
+    Division,
+    Modulo,
+    Power,
+    PlusMinus,
+    ImplicitMultiplication,
+    Conversion,
+    Comparison,
+    And,
+    Or,
+    Not,
+    Conditional,
+    Piecewise,
+    Let,
+    Summation,
+    Product,
+    List,
+    Index,
+    Aggregation,
+    MatrixOperation,
+    StringLiteral,
+    TextOperation,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BuiltinFunction {
+    Sqrt,
+    Exp,
+    Ln,
+    Sin,
+    Cos,
+    Tan,
+    Abs,
+}
+
+/// Functions that reduce the values of their arguments, and of the lists among them, to one number.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Mean,
+    Median,
+    /// Sample standard deviation.
+    Stdev,
+    Min,
+    Max,
+    /// Number of values, or of characters when the only argument is a string.
+    Len,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MatrixFunction {
+    Transpose,
+    Determinant,
+    Inverse,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TextFunction {
+    Concat,
+    Upper,
+    Lower,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RoundingMode {
+    HalfEven,
+    HalfUp,
+    HalfDown,
+    Up,
+    Down,
+    Ceiling,
+    Floor,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum InexactDivision {
+    Round,
+    Error,
+}
+
+/// How integer division rounds a quotient that is not whole; `%` follows the same choice.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum IntegerDivision {
+    Truncate,
+    Floor,
+}
+
+/// What a floating point operation does when finite operands give NaN or an infinity,
+/// as in division by zero, `sqrt` of a negative number, `ln(0)` or overflow.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NonFinitePolicy {
+    Error,
+    /// Return the NaN or infinity as IEEE 754 defines it.
+    Propagate,
+    /// Return the given value instead.
+    Substitute(f64),
 }
 
 impl CharType {
     pub(crate) fn parse_char_type(character: char) -> CharType {
         match character {
             char_arg if char_arg.is_numeric() => CharType::Number,
-            char_arg if "+-*/^".contains(char_arg) => CharType::Operator,
+            char_arg if "+-*/%^±<>=!".contains(char_arg) => CharType::Operator,
             char_arg if char_arg.is_alphabetic() => CharType::Letter,
             '.' => CharType::Point,
             char_arg if char_arg.is_whitespace() => CharType::Whitespace,
             char_arg if "(){}[]".contains(char_arg) => CharType::Bracket,
+            '"' => CharType::Quote,
             _ => CharType::Unknown
         }
     }
 }
 
 impl OperatorType {
-    pub(crate) fn parse_operator_type(character: char) -> OperatorType {
-        match &character {
-            '+' => OperatorType::Add,
-            '-' => OperatorType::Subtract,
-            '*' => OperatorType::Multiply,
-            '/' => OperatorType::Divide,
-            '^' => OperatorType::Power,
-            _ => panic!("Unknown operator type. should never happen")
+    pub(crate) fn parse_operator_type(operator: &str) -> Option<OperatorType> {
+        match operator {
+            "+" => Some(OperatorType::Add),
+            "-" => Some(OperatorType::Subtract),
+            "*" => Some(OperatorType::Multiply),
+            "/" => Some(OperatorType::Divide),
+            "%" => Some(OperatorType::Modulo),
+            "^" => Some(OperatorType::Power),
+            "±" => Some(OperatorType::PlusMinus),
+            "<" => Some(OperatorType::Compare(ComparisonType::Less)),
+            "<=" => Some(OperatorType::Compare(ComparisonType::LessOrEqual)),
+            ">" => Some(OperatorType::Compare(ComparisonType::Greater)),
+            ">=" => Some(OperatorType::Compare(ComparisonType::GreaterOrEqual)),
+            "==" => Some(OperatorType::Compare(ComparisonType::Equal)),
+            "!=" => Some(OperatorType::Compare(ComparisonType::NotEqual)),
+            _ => None,
+        }
+    }
+
+    /// Operators written as words, which only count as operators after a complete expression.
+    pub(crate) fn parse_keyword(name: &str) -> Option<OperatorType> {
+        match name {
+            "to" => Some(OperatorType::Convert),
+            "and" => Some(OperatorType::And),
+            "or" => Some(OperatorType::Or),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_exp_type(&self) -> ExpressionType {
+        match self {
+            OperatorType::Add => ExpressionType::Addition,
+            OperatorType::Subtract => ExpressionType::Subtraction,
+            OperatorType::Multiply => ExpressionType::Multiplication,
+            OperatorType::Divide => ExpressionType::Division,
+            OperatorType::Modulo => ExpressionType::Modulo,
+            OperatorType::Power => ExpressionType::Power,
+            OperatorType::PlusMinus => ExpressionType::PlusMinus,
+            OperatorType::ImplicitMultiply => ExpressionType::ImplicitMultiplication,
+            OperatorType::Convert => ExpressionType::Conversion,
+            OperatorType::Compare(_) => ExpressionType::Comparison,
+            OperatorType::And => ExpressionType::And,
+            OperatorType::Or => ExpressionType::Or,
+        }
+    }
+}
+
+impl ExpressionType {
+    /// Binding strength of an operator; operands and brackets bind tighter than any operator.
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            ExpressionType::Or => 0,
+            ExpressionType::And => 1,
+            ExpressionType::Not => 2,
+            ExpressionType::Comparison => 3,
+            ExpressionType::Conversion => 4,
+            ExpressionType::Addition | ExpressionType::Subtraction => 5,
+            ExpressionType::Multiplication | ExpressionType::Division | ExpressionType::Modulo => 6,
+            ExpressionType::ImplicitMultiplication => 7,
+            ExpressionType::Power => 8,
+            ExpressionType::PlusMinus => 9,
+            _ => u8::MAX,
+        }
+    }
+}
+
+impl ComparisonType {
+    pub(crate) fn get_symbol(&self) -> &'static str {
+        match self {
+            ComparisonType::Less => "<",
+            ComparisonType::LessOrEqual => "<=",
+            ComparisonType::Greater => ">",
+            ComparisonType::GreaterOrEqual => ">=",
+            ComparisonType::Equal => "==",
+            ComparisonType::NotEqual => "!=",
+        }
+    }
+}
+
+impl BuiltinFunction {
+    pub(crate) fn parse_builtin_function(name: &str) -> Option<BuiltinFunction> {
+        match name {
+            "sqrt" => Some(BuiltinFunction::Sqrt),
+            "exp" => Some(BuiltinFunction::Exp),
+            "ln" => Some(BuiltinFunction::Ln),
+            "sin" => Some(BuiltinFunction::Sin),
+            "cos" => Some(BuiltinFunction::Cos),
+            "tan" => Some(BuiltinFunction::Tan),
+            "abs" => Some(BuiltinFunction::Abs),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        match self {
+            BuiltinFunction::Sqrt => "sqrt",
+            BuiltinFunction::Exp => "exp",
+            BuiltinFunction::Ln => "ln",
+            BuiltinFunction::Sin => "sin",
+            BuiltinFunction::Cos => "cos",
+            BuiltinFunction::Tan => "tan",
+            BuiltinFunction::Abs => "abs",
+        }
+    }
+}
+
+impl Aggregate {
+    pub(crate) fn parse_aggregate(name: &str) -> Option<Aggregate> {
+        match name {
+            "sum" => Some(Aggregate::Sum),
+            "mean" => Some(Aggregate::Mean),
+            "median" => Some(Aggregate::Median),
+            "stdev" => Some(Aggregate::Stdev),
+            "min" => Some(Aggregate::Min),
+            "max" => Some(Aggregate::Max),
+            "len" => Some(Aggregate::Len),
+            _ => None,
         }
     }
-}
\ No newline at end of file
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Mean => "mean",
+            Aggregate::Median => "median",
+            Aggregate::Stdev => "stdev",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
+            Aggregate::Len => "len",
+        }
+    }
+}
+
+impl MatrixFunction {
+    pub(crate) fn parse_matrix_function(name: &str) -> Option<MatrixFunction> {
+        match name {
+            "transpose" => Some(MatrixFunction::Transpose),
+            "det" => Some(MatrixFunction::Determinant),
+            "inv" => Some(MatrixFunction::Inverse),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        match self {
+            MatrixFunction::Transpose => "transpose",
+            MatrixFunction::Determinant => "det",
+            MatrixFunction::Inverse => "inv",
+        }
+    }
+}
+
+impl TextFunction {
+    pub(crate) fn parse_text_function(name: &str) -> Option<TextFunction> {
+        match name {
+            "concat" => Some(TextFunction::Concat),
+            "upper" => Some(TextFunction::Upper),
+            "lower" => Some(TextFunction::Lower),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn get_name(&self) -> &'static str {
+        match self {
+            TextFunction::Concat => "concat",
+            TextFunction::Upper => "upper",
+            TextFunction::Lower => "lower",
+        }
+    }
+}
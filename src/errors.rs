@@ -1,7 +1,19 @@
 use std::error::Error;
 use std::fmt::{Debug, Display, Formatter, Result};
+use std::ops::Range;
 use crate::enums::ExpressionType;
 
+/// Character positions of a node in the parsed text, the end excluded. Counted like the index of
+/// `InvalidCharacter`, from the start of the whole input.
+pub type Span = Range<usize>;
+
+fn write_span(f: &mut Formatter<'_>, span: &Option<Span>) -> Result {
+    match span {
+        None => Ok(()),
+        Some(span) => write!(f, " at {}..{}", span.start, span.end),
+    }
+}
+
 #[derive(Debug)]
 pub struct EmptyBuffer;
 
@@ -38,3 +50,257 @@ pub struct ParsingError {
 impl Display for ParsingError { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Parsing buffer error ({})", self.message) } }
 
 impl Error for ParsingError {}
+
+#[derive(Debug)]
+pub struct UnknownVariable {
+    pub name: String,
+}
+
+impl Display for UnknownVariable { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Unknown variable '{}'", self.name) } }
+
+impl Error for UnknownVariable {}
+
+#[derive(Debug)]
+pub struct MissingOperand {
+    pub exp_type: ExpressionType,
+}
+
+impl Display for MissingOperand { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "{:?} is missing its right side", self.exp_type) } }
+
+impl Error for MissingOperand {}
+
+#[derive(Debug)]
+pub struct ColumnLength {
+    pub name: String,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl Display for ColumnLength { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Column '{}' has {} rows, expected {}", self.name, self.actual, self.expected) } }
+
+impl Error for ColumnLength {}
+
+/// Fewer variable slots than a compiled program reads.
+#[derive(Debug)]
+pub struct SlotCount {
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl Display for SlotCount { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Expected {} variable slots, got {}", self.expected, self.actual) } }
+
+impl Error for SlotCount {}
+
+#[derive(Debug)]
+pub struct ArithmeticError {
+    pub message: &'static str,
+}
+
+impl Display for ArithmeticError { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Arithmetic error ({})", self.message) } }
+
+impl Error for ArithmeticError {}
+
+#[derive(Debug)]
+pub struct UnknownFunction {
+    pub name: String,
+}
+
+impl Display for UnknownFunction { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Unknown function '{}'", self.name) } }
+
+impl Error for UnknownFunction {}
+
+#[derive(Debug)]
+pub struct UnsupportedOperation {
+    pub name: &'static str,
+}
+
+impl Display for UnsupportedOperation { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Operation '{}' is not supported by this number type", self.name) } }
+
+impl Error for UnsupportedOperation {}
+
+/// Quantities of different dimensions combined by an operation that needs them to match.
+#[derive(Debug)]
+pub struct DimensionMismatch {
+    pub left: String,
+    pub right: String,
+    /// The offending expression, filled in once the error reaches the tree.
+    pub expression: Option<String>,
+}
+
+impl Display for DimensionMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.expression {
+            None => write!(f, "Incompatible dimensions {} and {}", self.left, self.right),
+            Some(expression) => write!(f, "Incompatible dimensions {} and {} in '{}'", self.left, self.right, expression),
+        }
+    }
+}
+
+impl Error for DimensionMismatch {}
+
+/// Lists or matrices whose shapes do not fit an operation, such as lists of different
+/// lengths combined element by element, or a matrix product of mismatched sizes.
+#[derive(Debug)]
+pub struct ShapeMismatch {
+    pub left: String,
+    pub right: String,
+    /// The offending expression, filled in once the error reaches the tree.
+    pub expression: Option<String>,
+    /// Where the offending expression was parsed from, filled in along with it.
+    pub span: Option<Span>,
+}
+
+impl Display for ShapeMismatch {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.expression {
+            None => write!(f, "Incompatible shapes {} and {}", self.left, self.right)?,
+            Some(expression) => write!(f, "Incompatible shapes {} and {} in '{}'", self.left, self.right, expression)?,
+        }
+        write_span(f, &self.span)
+    }
+}
+
+impl Error for ShapeMismatch {}
+
+/// A non-square matrix given to an operation that needs a square one, such as `det`.
+#[derive(Debug)]
+pub struct NotSquare {
+    pub shape: String,
+    /// The offending expression, filled in once the error reaches the tree.
+    pub expression: Option<String>,
+    /// Where the offending expression was parsed from, filled in along with it.
+    pub span: Option<Span>,
+}
+
+impl Display for NotSquare {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match &self.expression {
+            None => write!(f, "Expected a square matrix, got a {}", self.shape)?,
+            Some(expression) => write!(f, "Expected a square matrix, got a {} in '{}'", self.shape, expression)?,
+        }
+        write_span(f, &self.span)
+    }
+}
+
+impl Error for NotSquare {}
+
+/// A value of one type used where another is needed, such as a boolean in arithmetic.
+#[derive(Debug)]
+pub struct TypeMismatch {
+    pub expected: &'static str,
+    pub actual: &'static str,
+}
+
+impl Display for TypeMismatch { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Expected a {}, got a {}", self.expected, self.actual) } }
+
+impl Error for TypeMismatch {}
+
+/// Expressions that only the tree evaluator supports, such as comparisons and conditionals.
+#[derive(Debug)]
+pub struct NotCompilable {
+    pub exp_type: ExpressionType,
+}
+
+impl Display for NotCompilable { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "{:?} cannot be compiled", self.exp_type) } }
+
+impl Error for NotCompilable {}
+
+#[derive(Debug)]
+pub struct NotDifferentiable {
+    pub exp_type: ExpressionType,
+}
+
+impl Display for NotDifferentiable { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "{:?} has no symbolic derivative", self.exp_type) } }
+
+impl Error for NotDifferentiable {}
+
+/// Piecewise expression without a default, none of whose conditions hold.
+#[derive(Debug)]
+pub struct NoMatchingArm {
+    pub expression: String,
+}
+
+impl Display for NoMatchingArm { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "No arm of '{}' matches", self.expression) } }
+
+impl Error for NoMatchingArm {}
+
+#[derive(Debug)]
+pub struct ArgumentCount {
+    pub name: String,
+    pub expected: usize,
+    pub actual: usize,
+}
+
+impl Display for ArgumentCount { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Wrong number of arguments for '{}' (expected {}, got {})", self.name, self.expected, self.actual) } }
+
+impl Error for ArgumentCount {}
+
+/// Calls to user-defined functions nested deeper than `ExpressionSettings::max_call_depth`.
+#[derive(Debug)]
+pub struct RecursionLimit {
+    pub name: String,
+    pub limit: usize,
+}
+
+impl Display for RecursionLimit { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Call depth limit of {} exceeded in '{}'", self.limit, self.name) } }
+
+impl Error for RecursionLimit {}
+
+/// User-defined functions that call each other in a cycle, listed in call order.
+#[derive(Debug)]
+pub struct RecursionCycle {
+    pub functions: Vec<String>,
+}
+
+impl Display for RecursionCycle { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Functions call each other in a cycle: {}", self.functions.join(" -> ")) } }
+
+impl Error for RecursionCycle {}
+
+/// A `sum` or `prod` over more terms than `ExpressionSettings::max_iterations`.
+#[derive(Debug)]
+pub struct IterationLimit {
+    pub limit: usize,
+}
+
+impl Display for IterationLimit { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Iteration limit of {} exceeded", self.limit) } }
+
+impl Error for IterationLimit {}
+
+/// An index that is not the position of a list element.
+#[derive(Debug)]
+pub struct IndexOutOfRange {
+    pub index: String,
+    pub length: usize,
+}
+
+impl Display for IndexOutOfRange { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Index {} is out of range for a list of {} values", self.index, self.length) } }
+
+impl Error for IndexOutOfRange {}
+
+/// An aggregate that is undefined for no values, such as the mean.
+#[derive(Debug)]
+pub struct EmptyList {
+    pub aggregate: &'static str,
+}
+
+impl Display for EmptyList { fn fmt(&self, f: &mut Formatter<'_>) -> Result { write!(f, "Cannot take the {} of an empty list", self.aggregate) } }
+
+impl Error for EmptyList {}
+
+/// Makes an error movable between threads. The crate's own errors keep their type, so callers
+/// can still downcast them; any other error is replaced by its message.
+pub(crate) fn into_send(error: Box<dyn Error>) -> Box<dyn Error + Send + Sync> {
+    macro_rules! keep_type {
+        ($error:ident, $($kind:ty),*) => {
+            $(let $error = match $error.downcast::<$kind>() {
+                Ok(kept) => return kept,
+                Err(error) => error,
+            };)*
+        };
+    }
+    keep_type!(error, EmptyBuffer, InvalidCharacter, AttachImpossible, ParsingError, UnknownVariable, MissingOperand,
+        ColumnLength, SlotCount, ArithmeticError, UnknownFunction, UnsupportedOperation, DimensionMismatch, ShapeMismatch,
+        NotSquare, TypeMismatch, NotCompilable, NotDifferentiable, NoMatchingArm, ArgumentCount, RecursionLimit, RecursionCycle,
+        IterationLimit, IndexOutOfRange, EmptyList);
+    Box::from(error.to_string())
+}
@@ -1,205 +1,966 @@
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
-use crate::enums::ExpressionType;
-use crate::errors::AttachImpossible;
+use std::sync::Arc;
+use crate::batch::{BatchArgs, BatchScratch};
+use crate::bytecode::{Compiler, Instruction};
+use crate::closure::CompiledFn;
+use crate::definition::FunctionRegistry;
+use crate::derivative::{difference, function_derivative, literal, power, power_derivative, product, product_rule, quotient, sum};
+use crate::enums::{BuiltinFunction, ExpressionType, InexactDivision, IntegerDivision, NonFinitePolicy, OperatorType, RoundingMode};
+use crate::errors::{ArgumentCount, AttachImpossible, DimensionMismatch, MissingOperand, NotCompilable, NotDifferentiable, NotSquare, ShapeMismatch, SlotCount, Span, UnknownFunction, UnknownVariable};
+use crate::logic::{And, Comparison, Or};
+use crate::matrix::Matrix;
+use crate::number::{BinaryOperation, Number};
+use crate::value::Value;
 
-static EXP_SETTINGS: ExpressionSettings = ExpressionSettings::get_default();
+pub type ExpressionFn<N = f64> = Arc<dyn Fn(N) -> N + Send + Sync>;
 
-pub struct ExpressionArgs {
-    pub functions: HashMap<String, Box<dyn Fn(f64) -> f64>>,
-    pub variables: HashMap<String, f64>,
+/// The outermost scope, which callers fill in.
+pub type ExpressionArgs<N = f64> = Scope<'static, N>;
+
+/// Variables, functions and settings an expression is evaluated with. Bodies of `let`, `Σ` and `Π`
+/// are evaluated in an inner `Scope` that binds one name and refers to the enclosing one.
+#[derive(Clone)]
+pub struct Scope<'a, N: Number = f64> {
+    pub functions: HashMap<String, ExpressionFn<N>>,
+    /// Functions written in the expression language, such as `f(x) = x^2 + 1`.
+    pub definitions: FunctionRegistry<N>,
+    pub variables: HashMap<String, N>,
+    /// List-valued variables, such as a series of readings. A number variable of the same name takes precedence.
+    pub lists: HashMap<String, Vec<N>>,
+    /// Matrix-valued variables. Number and list variables of the same name take precedence.
+    pub matrices: HashMap<String, Matrix<N>>,
+    /// String-valued variables, such as labels. Variables of other types with the same name take precedence.
+    pub strings: HashMap<String, String>,
+    pub settings: ExpressionSettings,
+    /// Number of user-defined function calls being evaluated.
+    pub(crate) depth: usize,
+    /// Scope enclosing the body of a `let`, `Σ` or `Π`, whose variables are visible unless
+    /// the body's own binding shadows them.
+    pub(crate) parent: Option<&'a Scope<'a, N>>,
 }
 
+#[derive(Clone, Debug)]
 pub struct ExpressionSettings {
     pub f64_delta: f64,
+    /// Number of fractional digits kept by `Decimal` arithmetic.
+    pub decimal_scale: u32,
+    pub rounding_mode: RoundingMode,
+    /// What a `Decimal` division does when its result has more digits than `decimal_scale`.
+    pub inexact_division: InexactDivision,
+    pub integer_division: IntegerDivision,
+    pub non_finite: NonFinitePolicy,
+    /// How deeply calls to user-defined functions may nest, which bounds recursion.
+    pub max_call_depth: usize,
+    /// Maximum number of terms a single `sum` or `prod` may evaluate.
+    pub max_iterations: usize,
 }
 
 impl Default for ExpressionSettings {
     fn default() -> Self {
-        ExpressionSettings::get_default()
-    }
-}
-
-impl ExpressionSettings {
-    const fn get_default() -> Self {
         ExpressionSettings {
             f64_delta: 1e-10,
+            decimal_scale: 2,
+            rounding_mode: RoundingMode::HalfEven,
+            inexact_division: InexactDivision::Error,
+            integer_division: IntegerDivision::Truncate,
+            non_finite: NonFinitePolicy::Propagate,
+            max_call_depth: 100,
+            max_iterations: 10_000,
         }
     }
 }
 
-pub trait ExpressionClone {
-    fn clone_box(&self) -> Box<dyn Expression>;
+pub trait ExpressionClone<N: Number> {
+    fn clone_box(&self) -> Box<dyn Expression<N>>;
 }
 
-impl<T: 'static + Expression + Clone> ExpressionClone for T {
-    fn clone_box(&self) -> Box<dyn Expression> {
+impl<N: Number, T: 'static + Expression<N> + Clone> ExpressionClone<N> for T {
+    fn clone_box(&self) -> Box<dyn Expression<N>> {
         Box::new(self.clone())
     }
 }
 
 
 #[derive(Clone)]
-pub struct ScalarValue {
-    pub value: f64,
+pub struct ScalarValue<N: Number = f64> {
+    pub value: N,
+}
+
+/// Value the number type gives to a name, such as the imaginary unit `i` or a unit like `km`.
+#[derive(Clone)]
+pub struct Constant<N: Number = f64> {
+    pub name: String,
+    pub value: N,
+}
+
+#[derive(Clone)]
+pub struct Variable {
+    pub name: String,
+}
+
+#[derive(Clone)]
+pub struct Bracket<N: Number = f64> {
+    pub inner: Box<dyn Expression<N>>,
+}
+
+/// Call of a built-in function, of one supplied in `ExpressionArgs::functions`, or of one
+/// defined in `ExpressionArgs::definitions`.
+#[derive(Clone)]
+pub struct Function<N: Number = f64> {
+    pub name: String,
+    pub arguments: Vec<Box<dyn Expression<N>>>,
+}
+
+#[derive(Clone)]
+pub struct Addition<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+#[derive(Clone)]
+pub struct Subtraction<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
 }
 
 #[derive(Clone)]
-pub struct Addition {
-    pub left: Box<dyn Expression>,
-    pub right: Option<Box<dyn Expression>>,
+pub struct Multiplication<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
 }
 
 #[derive(Clone)]
-pub struct Subtraction {
-    pub left: Box<dyn Expression>,
-    pub right: Option<Box<dyn Expression>>,
+pub struct Division<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
 }
 
 #[derive(Clone)]
-pub struct Multiplication {
-    pub left: Box<dyn Expression>,
-    pub right: Option<Box<dyn Expression>>,
+pub struct Modulo<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
 }
 
 #[derive(Clone)]
-pub struct Division {
-    pub left: Box<dyn Expression>,
-    pub right: Option<Box<dyn Expression>>,
+pub struct Power<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
 }
 
-impl ExpressionArgs {
+/// Value with a standard uncertainty, written `12.3 ± 0.2`.
+#[derive(Clone)]
+pub struct PlusMinus<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Multiplication written without an operator, as in `5 m`. Binds tighter than `*` and `/`.
+#[derive(Clone)]
+pub struct ImplicitMultiplication<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Conversion of the left side to the unit on the right, written `x to km/h`.
+#[derive(Clone)]
+pub struct Conversion<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+impl<'a, N: Number> Scope<'a, N> {
     pub fn empty() -> Self {
         Self {
             functions: HashMap::new(),
+            definitions: FunctionRegistry::new(),
             variables: HashMap::new(),
+            lists: HashMap::new(),
+            matrices: HashMap::new(),
+            strings: HashMap::new(),
+            settings: ExpressionSettings::default(),
+            depth: 0,
+            parent: None,
+        }
+    }
+
+    /// Arguments that bind `name` on top of these ones, without copying them.
+    pub(crate) fn scope<'s>(&'s self, name: &str, value: Value<N>) -> Result<Scope<'s, N>, Box<dyn Error>> {
+        let mut scope = Scope { settings: self.settings.clone(), depth: self.depth, parent: Some(self), ..Scope::empty() };
+        scope.bind(name, value)?;
+        Ok(scope)
+    }
+
+    /// The outermost arguments, which hold the functions and definitions.
+    pub(crate) fn root(&self) -> &Scope<'a, N> {
+        match self.parent {
+            None => self,
+            Some(parent) => parent.root(),
+        }
+    }
+
+    fn binds(&self, name: &str) -> bool {
+        self.variables.contains_key(name) || self.lists.contains_key(name) || self.matrices.contains_key(name) || self.strings.contains_key(name)
+            || self.parent.is_some_and(|parent| parent.binds(name))
+    }
+
+    /// Value of the variable, looked up from the innermost scope outwards.
+    fn lookup(&self, name: &str) -> Option<Value<N>> {
+        if let Some(value) = self.variables.get(name) {
+            return Some(Value::Number(value.clone()));
+        }
+        if let Some(values) = self.lists.get(name) {
+            return Some(Value::List(values.clone()));
+        }
+        if let Some(matrix) = self.matrices.get(name) {
+            return Some(Value::Matrix(matrix.clone()));
+        }
+        if let Some(text) = self.strings.get(name) {
+            return Some(Value::String(text.clone()));
         }
+        self.parent.and_then(|parent| parent.lookup(name))
+    }
+
+    /// Binds `name` to a number, list, matrix or string, replacing any variable of that name.
+    pub(crate) fn bind(&mut self, name: &str, value: Value<N>) -> Result<(), Box<dyn Error>> {
+        self.variables.remove(name);
+        self.lists.remove(name);
+        self.matrices.remove(name);
+        self.strings.remove(name);
+        match value {
+            Value::List(values) => {
+                self.lists.insert(name.to_string(), values);
+            }
+            Value::Matrix(matrix) => {
+                self.matrices.insert(name.to_string(), matrix);
+            }
+            Value::String(text) => {
+                self.strings.insert(name.to_string(), text);
+            }
+            value => {
+                self.variables.insert(name.to_string(), value.into_number()?);
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the values of the given variables, in the given order.
+    pub fn values<S: AsRef<str>>(&self, names: &[S]) -> Result<Vec<N>, Box<dyn Error>> {
+        names.iter()
+            .map(|name| match self.variables.get(name.as_ref()) {
+                None => Err(Box::from(UnknownVariable { name: name.as_ref().to_string() }) as Box<dyn Error>),
+                Some(value) => Ok(value.clone()),
+            })
+            .collect()
     }
 }
 
-impl Clone for Box<dyn Expression> {
-    fn clone(&self) -> Box<dyn Expression> {
+impl<N: Number> Clone for Box<dyn Expression<N>> {
+    fn clone(&self) -> Box<dyn Expression<N>> {
         self.clone_box()
     }
 }
 
-pub trait Expression: ExpressionClone {
-    fn can_evaluate(&self, _args: &ExpressionArgs) -> bool { true }
-    fn evaluate(&self, args: &ExpressionArgs) -> f64;
+pub trait Expression<N: Number = f64>: ExpressionClone<N> + Send + Sync {
+    fn can_evaluate(&self, _args: &Scope<N>) -> bool { true }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>>;
+    /// Evaluates expressions that may give something other than a number, such as comparisons.
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        Ok(Value::Number(self.evaluate(args)?))
+    }
+    /// Name of the called function, for function calls.
+    fn function_name(&self) -> Option<&str> { None }
+    /// Name of the index variable, for sums and products over a range.
+    fn bound_variable(&self) -> Option<&str> { None }
     fn to_string(&self) -> String;
     fn get_exp_type(&self) -> ExpressionType;
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>>;
+    fn children(&self) -> Vec<&dyn Expression<N>>;
+    /// Where the expression was parsed from, for the nodes that record it: operators, list literals
+    /// and matrix functions. Errors located at the node report it.
+    fn span(&self) -> Option<Span> { None }
+    /// Emits bytecode for the expression. All three compiled forms default to `NotCompilable`.
+    fn compile(&self, _compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        Err(Box::from(NotCompilable { exp_type: self.get_exp_type() }))
+    }
+    fn to_closure(&self, _variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        Err(Box::from(NotCompilable { exp_type: self.get_exp_type() }))
+    }
+    fn evaluate_batch(&self, _args: &BatchArgs<N>, _offset: usize, _output: &mut [N], _scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        Err(Box::from(NotCompilable { exp_type: self.get_exp_type() }))
+    }
+    /// Symbolic derivative with respect to `variable`. Defaults to `NotDifferentiable`.
+    fn differentiate(&self, _variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        Err(Box::from(NotDifferentiable { exp_type: self.get_exp_type() }))
+    }
+    /// Whether every operator in the expression has its right operand.
+    fn is_complete(&self) -> bool { true }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>>;
+    /// Applies an operator that follows the expression, taking over the right operand when it binds tighter.
+    /// `span` is where the operator stands in the parsed text.
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        Ok(new_operation(operator, self.clone_box(), span))
+    }
+}
+
+pub(crate) fn binary_children<'a, N: Number>(left: &'a dyn Expression<N>, right: &'a Option<Box<dyn Expression<N>>>) -> Vec<&'a dyn Expression<N>> {
+    match right {
+        None => vec![left],
+        Some(exp_box) => vec![left, exp_box.as_ref()],
+    }
 }
 
-impl Display for dyn Expression { fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.to_string()) } }
+pub(crate) fn attach_to_operand<N: Number>(operand: &dyn Expression<N>, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    Err(Box::from(AttachImpossible { target_type: operand.get_exp_type(), attach_type: exp.get_exp_type() }))
+}
+
+fn new_operation<N: Number>(operator: OperatorType, left: Box<dyn Expression<N>>, span: Option<Span>) -> Box<dyn Expression<N>> {
+    match operator {
+        OperatorType::Add => Box::from(Addition { left, right: None, span }),
+        OperatorType::Subtract => Box::from(Subtraction { left, right: None, span }),
+        OperatorType::Multiply => Box::from(Multiplication { left, right: None, span }),
+        OperatorType::Divide => Box::from(Division { left, right: None, span }),
+        OperatorType::Modulo => Box::from(Modulo { left, right: None, span }),
+        OperatorType::Power => Box::from(Power { left, right: None, span }),
+        OperatorType::PlusMinus => Box::from(PlusMinus { left, right: None, span }),
+        OperatorType::ImplicitMultiply => Box::from(ImplicitMultiplication { left, right: None, span }),
+        OperatorType::Convert => Box::from(Conversion { left, right: None, span }),
+        OperatorType::Compare(comparison) => Box::from(Comparison { comparison, left, right: None, span }),
+        OperatorType::And => Box::from(And { left, right: None, span }),
+        OperatorType::Or => Box::from(Or { left, right: None, span }),
+    }
+}
+
+pub(crate) fn binary_is_complete<N: Number>(right: &Option<Box<dyn Expression<N>>>) -> bool {
+    match right {
+        None => false,
+        Some(exp_box) => exp_box.as_ref().is_complete(),
+    }
+}
+
+pub(crate) fn binary_attach_after<N: Number>(exp: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, rebuild: impl FnOnce(Box<dyn Expression<N>>) -> Box<dyn Expression<N>>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    match right {
+        None => Ok(rebuild(exp.clone_box())),
+        Some(exp_box) => Ok(rebuild(exp_box.as_ref().attach_after(exp)?)),
+    }
+}
+
+/// `^` is right-associative, so it also takes over the right operand of another `^`.
+pub(crate) fn binary_attach_operator<N: Number>(exp: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, operator: OperatorType, span: Option<Span>, rebuild: impl FnOnce(Box<dyn Expression<N>>) -> Box<dyn Expression<N>>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let precedence = operator.get_exp_type().precedence();
+    let own_precedence = exp.get_exp_type().precedence();
+    match right {
+        None => Err(Box::from(AttachImpossible { target_type: exp.get_exp_type(), attach_type: operator.get_exp_type() })),
+        Some(exp_box) if precedence > own_precedence || (precedence == own_precedence && operator == OperatorType::Power) => {
+            Ok(rebuild(exp_box.as_ref().attach_operator(operator, span)?))
+        }
+        Some(_) => Ok(new_operation(operator, exp.clone_box(), span)),
+    }
+}
+
+fn right_operand<'a, N: Number>(exp: &dyn Expression<N>, right: &'a Option<Box<dyn Expression<N>>>) -> Result<&'a dyn Expression<N>, Box<dyn Error>> {
+    match right {
+        None => Err(Box::from(MissingOperand { exp_type: exp.get_exp_type() })),
+        Some(exp_box) => Ok(exp_box.as_ref()),
+    }
+}
+
+fn evaluate_binary<N: Number>(exp: &dyn Expression<N>, left: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, args: &Scope<N>, operation: BinaryOperation<N>) -> Result<N, Box<dyn Error>> {
+    evaluate_binary_value(exp, left, right, args, operation)?.into_number()
+}
 
-impl Expression for ScalarValue {
-    fn evaluate(&self, _args: &ExpressionArgs) -> f64 { self.value }
-    fn to_string(&self) -> String { format!("{}", self.value) }
+/// Arithmetic on lists applies element by element, see `Value::broadcast`.
+fn evaluate_binary_value<N: Number>(exp: &dyn Expression<N>, left: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, args: &Scope<N>, operation: BinaryOperation<N>) -> Result<Value<N>, Box<dyn Error>> {
+    match right {
+        None => Err(Box::from(MissingOperand { exp_type: exp.get_exp_type() })),
+        Some(exp_box) => left.evaluate_value(args)?
+            .broadcast(&exp_box.as_ref().evaluate_value(args)?, |left, right| operation(left, right, &args.settings))
+            .map_err(|e| locate_error(exp, e)),
+    }
+}
+
+/// Records the expression an operation failed in, for errors that point at the offending node.
+pub(crate) fn locate_error<N: Number>(exp: &dyn Expression<N>, error: Box<dyn Error>) -> Box<dyn Error> {
+    let error = match error.downcast::<DimensionMismatch>() {
+        Ok(mut mismatch) => {
+            mismatch.expression.get_or_insert_with(|| exp.to_string());
+            return mismatch;
+        }
+        Err(error) => error,
+    };
+    let error = match error.downcast::<ShapeMismatch>() {
+        Ok(mut mismatch) => {
+            if mismatch.expression.is_none() {
+                mismatch.expression = Some(exp.to_string());
+                mismatch.span = exp.span();
+            }
+            return mismatch;
+        }
+        Err(error) => error,
+    };
+    match error.downcast::<NotSquare>() {
+        Ok(mut not_square) => {
+            if not_square.expression.is_none() {
+                not_square.expression = Some(exp.to_string());
+                not_square.span = exp.span();
+            }
+            not_square
+        }
+        Err(error) => error,
+    }
+}
+
+fn compile_binary<N: Number>(exp: &dyn Expression<N>, left: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, instruction: Instruction<N>, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+    match right {
+        None => Err(Box::from(MissingOperand { exp_type: exp.get_exp_type() })),
+        Some(exp_box) => {
+            left.compile(compiler)?;
+            exp_box.as_ref().compile(compiler)?;
+            compiler.emit(instruction);
+            Ok(())
+        }
+    }
+}
+
+fn binary_closure<N: Number>(exp: &dyn Expression<N>, left: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, variables: &[&str], operation: BinaryOperation<N>) -> Result<CompiledFn<N>, Box<dyn Error>> {
+    match right {
+        None => Err(Box::from(MissingOperand { exp_type: exp.get_exp_type() })),
+        Some(exp_box) => {
+            let left = left.to_closure(variables)?;
+            let right = exp_box.as_ref().to_closure(variables)?;
+            Ok(Box::new(move |slots, settings| operation(&left(slots, settings)?, &right(slots, settings)?, settings)))
+        }
+    }
+}
+
+/// Evaluates both operands into scratch buffers and combines them with `Number::apply_batch`.
+fn evaluate_binary_batch<N: Number>(left: &dyn Expression<N>, right: &Option<Box<dyn Expression<N>>>, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>, operator: OperatorType) -> Result<(), Box<dyn Error>> {
+    match right {
+        None => Err(Box::from(MissingOperand { exp_type: operator.get_exp_type() })),
+        Some(exp_box) => {
+            let mut left_values = scratch.take(output);
+            let mut right_values = scratch.take(output);
+            left.evaluate_batch(args, offset, &mut left_values, scratch)?;
+            exp_box.as_ref().evaluate_batch(args, offset, &mut right_values, scratch)?;
+            N::apply_batch(operator, &left_values, &right_values, output, &args.settings)?;
+            scratch.give_back(right_values);
+            scratch.give_back(left_values);
+            Ok(())
+        }
+    }
+}
+
+impl<N: Number> Display for dyn Expression<N> { fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{}", self.to_string()) } }
+
+impl<N: Number> Expression<N> for ScalarValue<N> {
+    fn evaluate(&self, _args: &Scope<N>) -> Result<N, Box<dyn Error>> { Ok(self.value.clone()) }
+    fn to_string(&self) -> String { self.value.to_literal() }
     fn get_exp_type(&self) -> ExpressionType { ExpressionType::ScalarValue }
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>> {
-        match exp.get_exp_type() {
-            ExpressionType::ScalarValue => Err(Box::from(AttachImpossible { target_type: self.get_exp_type(), attach_type: exp.get_exp_type() })),
-            ExpressionType::Addition => Ok(Box::from(Addition {left: Box::from(self.clone()), right: None})),
-            ExpressionType::Subtraction => Ok(Box::from(Subtraction {left: Box::from(self.clone()), right: None})),
-            ExpressionType::Multiplication => Ok(Box::from(Multiplication {left: Box::from(self.clone()), right: None})),
-            ExpressionType::Division => Ok(Box::from(Division {left: Box::from(self.clone()), right: None})),
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![] }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compiler.emit(Instruction::Push(self.value.clone()));
+        Ok(())
+    }
+    fn to_closure(&self, _variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        let value = self.value.clone();
+        Ok(Box::new(move |_, _| Ok(value.clone())))
+    }
+    fn evaluate_batch(&self, _args: &BatchArgs<N>, _offset: usize, output: &mut [N], _scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        output.fill(self.value.clone());
+        Ok(())
+    }
+    fn differentiate(&self, _variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> { literal("0") }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Expression<N> for Constant<N> {
+    fn evaluate(&self, _args: &Scope<N>) -> Result<N, Box<dyn Error>> { Ok(self.value.clone()) }
+    fn to_string(&self) -> String { self.name.clone() }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Constant }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![] }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compiler.emit(Instruction::Push(self.value.clone()));
+        Ok(())
+    }
+    fn to_closure(&self, _variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        let value = self.value.clone();
+        Ok(Box::new(move |_, _| Ok(value.clone())))
+    }
+    fn evaluate_batch(&self, _args: &BatchArgs<N>, _offset: usize, output: &mut [N], _scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        output.fill(self.value.clone());
+        Ok(())
+    }
+    fn differentiate(&self, _variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> { literal("0") }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Expression<N> for Variable {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { args.binds(&self.name) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        args.lookup(&self.name).ok_or_else(|| Box::from(UnknownVariable { name: self.name.clone() }))
+    }
+    fn to_string(&self) -> String { self.name.clone() }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Variable }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![] }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        let slot = compiler.variable_slot(&self.name)?;
+        compiler.emit(Instruction::Load(slot));
+        Ok(())
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        match variables.iter().position(|variable| *variable == self.name) {
+            None => Err(Box::from(UnknownVariable { name: self.name.clone() })),
+            Some(slot) => {
+                let expected = variables.len();
+                Ok(Box::new(move |slots, _| slots.get(slot).cloned().ok_or_else(|| Box::from(SlotCount { expected, actual: slots.len() }) as Box<dyn Error>)))
+            }
         }
     }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], _scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        match args.columns.get(&self.name) {
+            None => Err(Box::from(UnknownVariable { name: self.name.clone() })),
+            Some(column) => {
+                output.clone_from_slice(&column[offset..offset + output.len()]);
+                Ok(())
+            }
+        }
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        literal(if self.name == variable { "1" } else { "0" })
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
 }
 
-impl Expression for Addition {
-    fn evaluate(&self, args: &ExpressionArgs) -> f64 {
-        match &self.right {
-            None => panic!("Attempt to evaluate sum with missing right side"),
-            Some(exp_box) => {
-                self.left.evaluate(args) +exp_box.as_ref().evaluate(args)
-            },
+impl<N: Number> Expression<N> for Bracket<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.inner.can_evaluate(args) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.inner.evaluate(args) }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.inner.evaluate_value(args) }
+    fn to_string(&self) -> String { format!("({})", self.inner) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Bracket }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.inner.as_ref()] }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> { self.inner.compile(compiler) }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> { self.inner.to_closure(variables) }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        self.inner.evaluate_batch(args, offset, output, scratch)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> { self.inner.differentiate(variable) }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Function<N> {
+    fn builtin(&self) -> Result<BuiltinFunction, Box<dyn Error>> {
+        BuiltinFunction::parse_builtin_function(&self.name).ok_or_else(|| Box::from(UnknownFunction { name: self.name.clone() }))
+    }
+
+    /// The argument of a built-in function or of one from `ExpressionArgs::functions`, which take exactly one.
+    fn single_argument(&self) -> Result<&dyn Expression<N>, Box<dyn Error>> {
+        match self.arguments.as_slice() {
+            [argument] => Ok(argument.as_ref()),
+            arguments => Err(Box::from(ArgumentCount { name: self.name.clone(), expected: 1, actual: arguments.len() })),
+        }
+    }
+
+    fn call(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        if let Ok(function) = self.builtin() {
+            let value = self.single_argument()?.evaluate_value(args)?;
+            return value.map(|value| value.apply_function(function, &args.settings)).map_err(|e| locate_error(self, e));
+        }
+        if let Some(function) = args.root().functions.get(&self.name) {
+            return Ok(Value::Number(function(self.single_argument()?.evaluate(args)?)));
+        }
+        let values = self.arguments.iter().map(|argument| argument.evaluate(args)).collect::<Result<Vec<N>, _>>()?;
+        args.root().definitions.call(&self.name, values, args)
+    }
+}
+
+/// Built-in functions take precedence over ones of the same name in `ExpressionArgs::functions`,
+/// which take precedence over definitions. Built-in functions apply to every element of a list.
+/// Only built-in functions can be compiled.
+impl<N: Number> Expression<N> for Function<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        let known = self.builtin().is_ok() || args.root().functions.contains_key(&self.name) || args.root().definitions.get(&self.name).is_some();
+        known && self.arguments.iter().all(|argument| argument.can_evaluate(args))
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.call(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.call(args) }
+    fn function_name(&self) -> Option<&str> { Some(&self.name) }
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> = self.arguments.iter().map(|argument| argument.to_string()).collect();
+        format!("{}({})", self.name, arguments.join(", "))
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Function }
+    fn children(&self) -> Vec<&dyn Expression<N>> { self.arguments.iter().map(|argument| argument.as_ref()).collect() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        let function = self.builtin()?;
+        self.single_argument()?.compile(compiler)?;
+        compiler.emit(Instruction::Call(function));
+        Ok(())
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        let function = self.builtin()?;
+        let argument = self.single_argument()?.to_closure(variables)?;
+        Ok(Box::new(move |slots, settings| argument(slots, settings)?.apply_function(function, settings)))
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        let function = self.builtin()?;
+        self.single_argument()?.evaluate_batch(args, offset, output, scratch)?;
+        for value in output.iter_mut() {
+            *value = value.apply_function(function, &args.settings)?;
+        }
+        Ok(())
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        match self.builtin() {
+            Ok(function) => function_derivative(function, self.single_argument()?, variable),
+            Err(_) => Err(Box::from(NotDifferentiable { exp_type: self.get_exp_type() })),
         }
     }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Expression<N> for Addition<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::add)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::add)
+    }
     fn to_string(&self) -> String {
         format!("{} + {}", self.left, self.right.as_ref().unwrap().clone_box())
     }
     fn get_exp_type(&self) -> ExpressionType { ExpressionType::Addition }
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>> {
-        match self.right {
-            None => { Ok(Box::from(Addition {left: self.left.clone_box(), right: Some(exp.clone_box())})) }
-            Some(_) => Err(Box::from(AttachImpossible { target_type: self.get_exp_type(), attach_type: exp.get_exp_type() })),
-        }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Add, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::add)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Add)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        Ok(sum(self.left.differentiate(variable)?, right.differentiate(variable)?))
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Addition { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Addition { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
     }
 }
 
-impl Expression for Subtraction {
-    fn evaluate(&self, args: &ExpressionArgs) -> f64 {
-        match &self.right {
-            None => panic!("Attempt to evaluate subtraction with missing right side"),
-            Some(exp_box) => {
-                self.left.evaluate(args) - exp_box.as_ref().evaluate(args)
-            },
-        }
+impl<N: Number> Expression<N> for Subtraction<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::subtract)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::subtract)
     }
     fn to_string(&self) -> String {
         format!("{} - {}", self.left, self.right.as_ref().unwrap().clone_box())
     }
     fn get_exp_type(&self) -> ExpressionType { ExpressionType::Subtraction }
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>> {
-        match self.right {
-            None => { Ok(Box::from(Subtraction {left: self.left.clone_box(), right: Some(exp.clone_box())})) }
-            Some(_) => Err(Box::from(AttachImpossible { target_type: self.get_exp_type(), attach_type: exp.get_exp_type() })),
-        }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Subtract, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::subtract)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Subtract)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        difference(self.left.differentiate(variable)?, right.differentiate(variable)?)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Subtraction { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Subtraction { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
     }
 }
 
-impl Expression for Multiplication {
-    fn evaluate(&self, args: &ExpressionArgs) -> f64 {
+impl<N: Number> Expression<N> for Multiplication<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    /// The matrix product when either side is a matrix, see `Value::product`.
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
         match &self.right {
-            None => panic!("Attempt to evaluate multiplication with missing right side"),
-            Some(exp_box) => {
-                self.left.evaluate(args) * exp_box.as_ref().evaluate(args)
-            },
+            None => Err(Box::from(MissingOperand { exp_type: self.get_exp_type() })),
+            Some(exp_box) => self.left.evaluate_value(args)?
+                .product(&exp_box.as_ref().evaluate_value(args)?, &args.settings)
+                .map_err(|e| locate_error(self, e)),
         }
     }
     fn to_string(&self) -> String {
         format!("{} * {}", self.left, self.right.as_ref().unwrap().clone_box())
     }
     fn get_exp_type(&self) -> ExpressionType { ExpressionType::Multiplication }
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>> {
-        match self.right {
-            None => { Ok(Box::from(Multiplication {left: self.left.clone_box(), right: Some(exp.clone_box())})) }
-            Some(_) => Err(Box::from(AttachImpossible { target_type: self.get_exp_type(), attach_type: exp.get_exp_type() })),
-        }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Multiply, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::multiply)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Multiply)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        product_rule(self.left.as_ref(), right, variable)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Multiplication { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Multiplication { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
     }
 }
 
-impl Expression for Division {
-    fn can_evaluate(&self, args: &ExpressionArgs) -> bool {
+impl<N: Number> Expression<N> for Division<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
         match &self.right {
-            None => panic!("Attempt to evaluate division with missing right side"),
-            Some(exp_box) => {
-                exp_box.as_ref().evaluate(args).abs() > EXP_SETTINGS.f64_delta
+            None => false,
+            Some(exp_box) => match exp_box.as_ref().evaluate(args) {
+                Ok(value) => !value.is_zero(args.settings.f64_delta),
+                Err(_) => false,
             },
         }
     }
-    fn evaluate(&self, args: &ExpressionArgs) -> f64 {
-        match &self.right {
-            None => panic!("Attempt to evaluate division with missing right side"),
-            Some(exp_box) => {
-                self.left.evaluate(args) / exp_box.as_ref().evaluate(args)
-            },
-        }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::divide)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::divide)
     }
     fn to_string(&self) -> String {
         format!("{} / {}", self.left, self.right.as_ref().unwrap().clone_box())
     }
     fn get_exp_type(&self) -> ExpressionType { ExpressionType::Division }
-    fn attach_after(&self, exp: &Box<dyn Expression>) -> Result<Box<dyn Expression>, Box<dyn Error>> {
-        match self.right {
-            None => { Ok(Box::from(Division {left: self.left.clone_box(), right: Some(exp.clone_box())})) }
-            Some(_) => Err(Box::from(AttachImpossible { target_type: self.get_exp_type(), attach_type: exp.get_exp_type() })),
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Divide, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::divide)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Divide)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        let left = self.left.as_ref();
+        let numerator = difference(product(left.differentiate(variable)?, right.clone_box()), product(left.clone_box(), right.differentiate(variable)?))?;
+        Ok(quotient(numerator, power(right.clone_box(), literal("2")?)))
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Division { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Division { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
+
+impl<N: Number> Expression<N> for Modulo<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        match &self.right {
+            None => false,
+            Some(exp_box) => match exp_box.as_ref().evaluate(args) {
+                Ok(value) => !value.is_zero(args.settings.f64_delta),
+                Err(_) => false,
+            },
         }
     }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::remainder)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::remainder)
+    }
+    fn to_string(&self) -> String {
+        format!("{} % {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Modulo }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Remainder, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::remainder)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Modulo)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Modulo { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Modulo { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
 }
 
+impl<N: Number> Expression<N> for Power<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::power)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::power)
+    }
+    fn to_string(&self) -> String {
+        format!("{} ^ {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Power }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Power, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::power)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Power)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        power_derivative(self.left.as_ref(), right, variable)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Power { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Power { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
+
+impl<N: Number> Expression<N> for PlusMinus<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::with_uncertainty)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::with_uncertainty)
+    }
+    fn to_string(&self) -> String {
+        format!("{} ± {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::PlusMinus }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::PlusMinus, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::with_uncertainty)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::PlusMinus)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(PlusMinus { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(PlusMinus { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
 
+impl<N: Number> Expression<N> for ImplicitMultiplication<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::multiply)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::multiply)
+    }
+    fn to_string(&self) -> String {
+        format!("{} {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::ImplicitMultiplication }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Multiply, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::multiply)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::ImplicitMultiply)
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let right = right_operand(self, &self.right)?;
+        product_rule(self.left.as_ref(), right, variable)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(ImplicitMultiplication { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(ImplicitMultiplication { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
 
+impl<N: Number> Expression<N> for Conversion<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        evaluate_binary(self, self.left.as_ref(), &self.right, args, N::convert)
+    }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        evaluate_binary_value(self, self.left.as_ref(), &self.right, args, N::convert)
+    }
+    fn to_string(&self) -> String {
+        format!("{} to {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Conversion }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn compile(&self, compiler: &mut Compiler<N>) -> Result<(), Box<dyn Error>> {
+        compile_binary(self, self.left.as_ref(), &self.right, Instruction::Convert, compiler)
+    }
+    fn to_closure(&self, variables: &[&str]) -> Result<CompiledFn<N>, Box<dyn Error>> {
+        binary_closure(self, self.left.as_ref(), &self.right, variables, N::convert)
+    }
+    fn evaluate_batch(&self, args: &BatchArgs<N>, offset: usize, output: &mut [N], scratch: &mut BatchScratch<N>) -> Result<(), Box<dyn Error>> {
+        evaluate_binary_batch(self.left.as_ref(), &self.right, args, offset, output, scratch, OperatorType::Convert)
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Conversion { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Conversion { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
@@ -0,0 +1,339 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::f64::consts::PI;
+use std::fmt::{Display, Formatter};
+use crate::enums::BuiltinFunction;
+use crate::errors::ArithmeticError;
+use crate::expression::ExpressionSettings;
+use crate::number::Number;
+
+/// Closed interval guaranteed to contain the exact result. Every bound is rounded outward,
+/// so the enclosure stays sound despite floating point rounding.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Interval {
+    lower: f64,
+    upper: f64,
+}
+
+const ENTIRE: Interval = Interval { lower: f64::NEG_INFINITY, upper: f64::INFINITY };
+
+/// Rounds `value` down, given the sign of the error `exact - value`.
+fn round_down(value: f64, error: f64) -> f64 {
+    if value == f64::INFINITY {
+        f64::MAX
+    } else if error < 0.0 {
+        value.next_down()
+    } else {
+        value
+    }
+}
+
+/// Rounds `value` up, given the sign of the error `exact - value`.
+fn round_up(value: f64, error: f64) -> f64 {
+    if value == f64::NEG_INFINITY {
+        f64::MIN
+    } else if error > 0.0 {
+        value.next_up()
+    } else {
+        value
+    }
+}
+
+/// Returns `a + b` and its exact rounding error.
+fn sum_with_error(a: f64, b: f64) -> (f64, f64) {
+    let sum = a + b;
+    let b_part = sum - a;
+    (sum, (a - (sum - b_part)) + (b - b_part))
+}
+
+/// Returns `a * b` and its exact rounding error. Zero times anything is zero, infinities included.
+fn product_with_error(a: f64, b: f64) -> (f64, f64) {
+    if a == 0.0 || b == 0.0 {
+        return (0.0, 0.0);
+    }
+    let product = a * b;
+    (product, a.mul_add(b, -product))
+}
+
+/// Returns `a / b` and a value with the sign of its rounding error.
+fn quotient_with_error(a: f64, b: f64) -> (f64, f64) {
+    let quotient = a / b;
+    // the remainder of a rounded division is exactly representable
+    let remainder = -quotient.mul_add(b, -a);
+    (quotient, if b < 0.0 { -remainder } else { remainder })
+}
+
+fn multiply_down(a: f64, b: f64) -> f64 {
+    let (product, error) = product_with_error(a, b);
+    round_down(product, error)
+}
+
+fn multiply_up(a: f64, b: f64) -> f64 {
+    let (product, error) = product_with_error(a, b);
+    round_up(product, error)
+}
+
+/// Bounds of `base^exponent` for a non-negative base, squaring with rounding in both directions.
+fn power_bounds(base: f64, mut exponent: u32) -> (f64, f64) {
+    let (mut result_down, mut result_up) = (1.0, 1.0);
+    let (mut base_down, mut base_up) = (base, base);
+    while exponent > 0 {
+        if exponent & 1 == 1 {
+            result_down = multiply_down(result_down, base_down);
+            result_up = multiply_up(result_up, base_up);
+        }
+        exponent >>= 1;
+        if exponent > 0 {
+            base_down = multiply_down(base_down, base_down);
+            base_up = multiply_up(base_up, base_up);
+        }
+    }
+    (result_down, result_up)
+}
+
+/// Results of library functions are within one ulp, so they are widened by one ulp.
+fn widen(lower: f64, upper: f64) -> Interval {
+    Interval { lower: lower.next_down(), upper: upper.next_up() }
+}
+
+fn domain_error() -> Box<dyn Error> {
+    Box::from(ArithmeticError { message: "Interval is outside the function's domain" })
+}
+
+impl Interval {
+    pub fn new(lower: f64, upper: f64) -> Result<Interval, Box<dyn Error>> {
+        if lower.is_nan() || upper.is_nan() || lower > upper {
+            return Err(Box::from(ArithmeticError { message: "Invalid interval bounds" }));
+        }
+        Ok(Interval { lower, upper })
+    }
+
+    pub fn point(value: f64) -> Interval {
+        Interval { lower: value, upper: value }
+    }
+
+    pub fn lower(&self) -> f64 { self.lower }
+
+    pub fn upper(&self) -> f64 { self.upper }
+
+    pub fn width(&self) -> f64 { self.upper - self.lower }
+
+    pub fn contains(&self, value: f64) -> bool { self.lower <= value && value <= self.upper }
+
+    fn hull(bounds: [(f64, f64); 4]) -> Interval {
+        let lower = bounds.iter().map(|(lower, _)| *lower).fold(f64::INFINITY, f64::min);
+        let upper = bounds.iter().map(|(_, upper)| *upper).fold(f64::NEG_INFINITY, f64::max);
+        Interval { lower, upper }
+    }
+
+    fn rounded_corners(&self, other: &Interval, operation: fn(f64, f64) -> (f64, f64)) -> Interval {
+        let corners = [(self.lower, other.lower), (self.lower, other.upper), (self.upper, other.lower), (self.upper, other.upper)];
+        Interval::hull(corners.map(|(a, b)| {
+            let (value, error) = operation(a, b);
+            (round_down(value, error), round_up(value, error))
+        }))
+    }
+
+    /// Ranges of sine and cosine. `offset` shifts the extrema: cosine peaks at even multiples
+    /// of pi and sine at even multiples of pi plus pi / 2.
+    fn periodic(&self, offset: f64, function: fn(f64) -> f64) -> Interval {
+        if self.width() >= 2.0 * PI || !self.width().is_finite() {
+            return Interval { lower: -1.0, upper: 1.0 };
+        }
+        // extrema are found on a slightly widened interval, which can only loosen the result
+        let first = ((self.lower / PI - offset).next_down().next_down()).ceil();
+        let last = ((self.upper / PI - offset).next_up().next_up()).floor();
+        let at_lower = function(self.lower);
+        let at_upper = function(self.upper);
+        let mut result = widen(at_lower.min(at_upper), at_lower.max(at_upper));
+        let mut extremum = first;
+        while extremum <= last {
+            if extremum.rem_euclid(2.0) == 0.0 { result.upper = 1.0 } else { result.lower = -1.0 }
+            extremum += 1.0;
+        }
+        Interval { lower: result.lower.max(-1.0), upper: result.upper.min(1.0) }
+    }
+
+    fn tan(&self) -> Interval {
+        // a pole at an odd multiple of pi / 2 inside the interval makes it unbounded
+        let first_pole = ((self.lower / PI - 0.5).next_down().next_down()).ceil();
+        let last_pole = ((self.upper / PI - 0.5).next_up().next_up()).floor();
+        if first_pole <= last_pole || !self.width().is_finite() {
+            return ENTIRE;
+        }
+        widen(self.lower.tan(), self.upper.tan())
+    }
+
+    fn exp(&self) -> Interval {
+        let result = widen(self.lower.exp(), self.upper.exp());
+        Interval { lower: result.lower.max(0.0), upper: result.upper }
+    }
+
+    /// Logarithm over the positive part of the interval.
+    fn ln(&self) -> Result<Interval, Box<dyn Error>> {
+        if self.upper <= 0.0 {
+            return Err(domain_error());
+        }
+        let lower = if self.lower <= 0.0 { f64::NEG_INFINITY } else { self.lower.ln() };
+        Ok(widen(lower, self.upper.ln()))
+    }
+
+    /// Square root over the non-negative part of the interval.
+    fn sqrt(&self) -> Result<Interval, Box<dyn Error>> {
+        if self.upper < 0.0 {
+            return Err(domain_error());
+        }
+        let rounded_sqrt = |value: f64| {
+            let root = value.sqrt();
+            (root, -root.mul_add(root, -value))
+        };
+        let (lower, lower_error) = rounded_sqrt(self.lower.max(0.0));
+        let (upper, upper_error) = rounded_sqrt(self.upper);
+        Ok(Interval { lower: round_down(lower, lower_error), upper: round_up(upper, upper_error) })
+    }
+
+    fn abs(&self) -> Interval {
+        if self.lower >= 0.0 {
+            *self
+        } else if self.upper <= 0.0 {
+            Interval { lower: -self.upper, upper: -self.lower }
+        } else {
+            Interval { lower: 0.0, upper: self.upper.max(-self.lower) }
+        }
+    }
+
+    fn integer_power(&self, exponent: u32) -> Interval {
+        if exponent == 0 {
+            return Interval::point(1.0);
+        }
+        let signed_bounds = |value: f64| {
+            let (down, up) = power_bounds(value.abs(), exponent);
+            if value < 0.0 && exponent % 2 == 1 { (-up, -down) } else { (down, up) }
+        };
+        let (lower_down, lower_up) = signed_bounds(self.lower);
+        let (upper_down, upper_up) = signed_bounds(self.upper);
+        if exponent % 2 == 1 || self.lower >= 0.0 {
+            Interval { lower: lower_down, upper: upper_up }
+        } else if self.upper <= 0.0 {
+            Interval { lower: upper_down, upper: lower_up }
+        } else {
+            Interval { lower: 0.0, upper: lower_up.max(upper_up) }
+        }
+    }
+}
+
+impl Display for Interval {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {}]", self.lower, self.upper)
+    }
+}
+
+impl Number for Interval {
+    /// Literals that are not exactly representable are widened to the neighbouring floats.
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+        let value = literal.parse::<f64>()?;
+        if value.fract() == 0.0 && value.abs() <= 2f64.powi(53) {
+            return Ok(Interval::point(value));
+        }
+        Ok(widen(value, value))
+    }
+
+    /// Prints the literal that parses back to this interval: a point or a widened float prints
+    /// as its value. Any other interval has no literal form, and prints as `interval(lo, hi)`
+    /// rather than the list syntax `[lo, hi]`.
+    fn to_literal(&self) -> String {
+        let value = self.lower.next_up();
+        if self.lower == self.upper {
+            self.lower.to_literal()
+        } else if self.upper == value.next_up() && Interval::parse_literal(&value.to_string()).ok() == Some(*self) {
+            value.to_string()
+        } else {
+            format!("interval({}, {})", self.lower, self.upper)
+        }
+    }
+
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let (lower, lower_error) = sum_with_error(self.lower, other.lower);
+        let (upper, upper_error) = sum_with_error(self.upper, other.upper);
+        Ok(Interval { lower: round_down(lower, lower_error), upper: round_up(upper, upper_error) })
+    }
+
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let (lower, lower_error) = sum_with_error(self.lower, -other.upper);
+        let (upper, upper_error) = sum_with_error(self.upper, -other.lower);
+        Ok(Interval { lower: round_down(lower, lower_error), upper: round_up(upper, upper_error) })
+    }
+
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.rounded_corners(other, product_with_error))
+    }
+
+    /// A divisor containing zero splits the result in two; the enclosing interval of both
+    /// parts is returned, which is unbounded on at least one side.
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if other.lower > 0.0 || other.upper < 0.0 {
+            return Ok(self.rounded_corners(other, quotient_with_error));
+        }
+        if other.lower == 0.0 && other.upper == 0.0 {
+            return Err(Box::from(ArithmeticError { message: "Division by zero" }));
+        }
+        if self.contains(0.0) || (other.lower < 0.0 && other.upper > 0.0) {
+            return Ok(ENTIRE);
+        }
+        // the divisor is [0, d] or [c, 0] and the dividend does not contain zero
+        let nearest = if self.upper < 0.0 { self.upper } else { self.lower };
+        let (value, error) = quotient_with_error(nearest, other.lower + other.upper);
+        if value > 0.0 {
+            Ok(Interval { lower: round_down(value, error), upper: f64::INFINITY })
+        } else {
+            Ok(Interval { lower: f64::NEG_INFINITY, upper: round_up(value, error) })
+        }
+    }
+
+    /// Integer exponents work for any base; other exponents only use the non-negative part of the base.
+    fn power(&self, exponent: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if exponent.lower == exponent.upper && exponent.lower.fract() == 0.0 && exponent.lower.abs() <= i32::MAX as f64 {
+            let power = self.integer_power((exponent.lower as i32).unsigned_abs());
+            return match exponent.lower < 0.0 {
+                false => Ok(power),
+                true => Interval::point(1.0).divide(&power, settings),
+            };
+        }
+        exponent.multiply(&self.ln()?, settings).map(|logarithm| logarithm.exp())
+    }
+
+    fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        match function {
+            BuiltinFunction::Sqrt => self.sqrt(),
+            BuiltinFunction::Exp => Ok(self.exp()),
+            BuiltinFunction::Ln => self.ln(),
+            BuiltinFunction::Sin => Ok(self.periodic(0.5, f64::sin)),
+            BuiltinFunction::Cos => Ok(self.periodic(0.0, f64::cos)),
+            BuiltinFunction::Tan => Ok(self.tan()),
+            BuiltinFunction::Abs => Ok(self.abs()),
+        }
+    }
+
+    /// Intervals are ordered only when they do not overlap, since the ordering of the values
+    /// they enclose is unknown otherwise.
+    fn compare(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+        if self.upper < other.lower {
+            Ok(Ordering::Less)
+        } else if self.lower > other.upper {
+            Ok(Ordering::Greater)
+        } else if self.lower == self.upper && *self == *other {
+            Ok(Ordering::Equal)
+        } else {
+            Err(Box::from(ArithmeticError { message: "Overlapping intervals cannot be ordered" }))
+        }
+    }
+
+    /// Only an interval inside `[-delta, delta]` counts as zero; one that merely contains zero does not.
+    fn is_zero(&self, delta: f64) -> bool { -delta <= self.lower && self.upper <= delta }
+    fn to_index(&self) -> Option<usize> {
+        match self.lower == self.upper {
+            true => self.lower.to_index(),
+            false => None,
+        }
+    }
+}
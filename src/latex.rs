@@ -0,0 +1,53 @@
+use crate::enums::{BuiltinFunction, ExpressionType};
+use crate::expression::Expression;
+use crate::number::Number;
+
+impl<N: Number> dyn Expression<N> {
+    /// LaTeX for the expression, with sums and products over a range written as `\sum` and `\prod`.
+    /// Expressions without a LaTeX form of their own, such as lists, are written as they print.
+    pub fn to_latex(&self) -> String {
+        render_latex(self)
+    }
+}
+
+fn render_latex<N: Number>(exp: &dyn Expression<N>) -> String {
+    let children = exp.children();
+    let operand = |index: usize| render_latex(children[index]);
+    match (exp.get_exp_type(), children.len()) {
+        (ExpressionType::Bracket, 1) => format!("\\left({}\\right)", operand(0)),
+        (ExpressionType::Function, _) => render_function(exp.function_name().unwrap_or_default(), &children),
+        (ExpressionType::Addition, 2) => format!("{} + {}", operand(0), operand(1)),
+        (ExpressionType::Subtraction, 2) => format!("{} - {}", operand(0), operand(1)),
+        (ExpressionType::Multiplication, 2) => format!("{} \\cdot {}", operand(0), operand(1)),
+        (ExpressionType::ImplicitMultiplication, 2) => format!("{} \\, {}", operand(0), operand(1)),
+        (ExpressionType::Division, 2) => format!("\\frac{{{}}}{{{}}}", operand(0), operand(1)),
+        (ExpressionType::Modulo, 2) => format!("{} \\bmod {}", operand(0), operand(1)),
+        (ExpressionType::Power, 2) => format!("{}^{{{}}}", operand(0), operand(1)),
+        (ExpressionType::PlusMinus, 2) => format!("{} \\pm {}", operand(0), operand(1)),
+        (ExpressionType::Summation, 3) => render_series("\\sum", exp, &children),
+        (ExpressionType::Product, 3) => render_series("\\prod", exp, &children),
+        _ => exp.to_string(),
+    }
+}
+
+fn render_function<N: Number>(name: &str, arguments: &[&dyn Expression<N>]) -> String {
+    let arguments: Vec<String> = arguments.iter().map(|argument| render_latex(*argument)).collect();
+    let arguments = arguments.join(", ");
+    match BuiltinFunction::parse_builtin_function(name) {
+        Some(BuiltinFunction::Sqrt) => format!("\\sqrt{{{}}}", arguments),
+        Some(BuiltinFunction::Abs) => format!("\\left|{}\\right|", arguments),
+        Some(function) => format!("\\{}\\left({}\\right)", function.get_name(), arguments),
+        None => format!("\\operatorname{{{}}}\\left({}\\right)", name, arguments),
+    }
+}
+
+/// The body is bracketed when it binds looser than a product, so that `\sum_{i=1}^{n} i + 1`
+/// cannot be read as the sum of `i + 1`.
+fn render_series<N: Number>(symbol: &str, exp: &dyn Expression<N>, children: &[&dyn Expression<N>]) -> String {
+    let variable = exp.bound_variable().unwrap_or_default();
+    let body = match children[2].get_exp_type().precedence() < ExpressionType::Multiplication.precedence() {
+        true => format!("\\left({}\\right)", render_latex(children[2])),
+        false => render_latex(children[2]),
+    };
+    format!("{}_{{{}={}}}^{{{}}} {}", symbol, variable, render_latex(children[0]), render_latex(children[1]), body)
+}
@@ -1,4 +1,25 @@
 pub mod expression;
 pub mod parser;
 pub mod errors;
-pub mod enums;
\ No newline at end of file
+pub mod enums;
+pub mod number;
+pub mod rational;
+pub mod decimal;
+pub mod complex;
+pub mod interval;
+pub mod autodiff;
+mod derivative;
+pub mod uncertainty;
+pub mod quantity;
+pub mod dot;
+pub mod latex;
+pub mod bytecode;
+pub mod closure;
+pub mod batch;
+pub mod value;
+pub mod logic;
+pub mod binding;
+pub mod definition;
+pub mod list;
+pub mod matrix;
+pub mod text;
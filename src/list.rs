@@ -0,0 +1,180 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use crate::enums::{Aggregate, BuiltinFunction, ExpressionType};
+use crate::errors::{ArithmeticError, EmptyList, IndexOutOfRange, Span};
+use crate::expression::{attach_to_operand, locate_error, Expression, ExpressionSettings, Scope};
+use crate::matrix::Matrix;
+use crate::number::Number;
+use crate::value::Value;
+
+/// List literal, written `[1, 2, 3]`. Arithmetic with lists applies element by element.
+/// A literal whose elements are lists, such as `[[1, 2], [3, 4]]`, is a matrix of those rows.
+#[derive(Clone)]
+pub struct ListLiteral<N: Number = f64> {
+    pub elements: Vec<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Element of a list, written `xs[i]` and counted from zero. Only named lists can be indexed,
+/// so a literal is bound first, as in `let xs = [1, 2, 3] in xs[1]`.
+#[derive(Clone)]
+pub struct Index<N: Number = f64> {
+    pub list: Box<dyn Expression<N>>,
+    pub index: Box<dyn Expression<N>>,
+}
+
+/// Aggregate over the values of its arguments, written `mean(xs)` or `max(a, b, xs)`.
+/// Lists and matrices among the arguments contribute each of their elements, and `len` of a
+/// single string counts its characters.
+#[derive(Clone)]
+pub struct Aggregation<N: Number = f64> {
+    pub aggregate: Aggregate,
+    pub arguments: Vec<Box<dyn Expression<N>>>,
+}
+
+fn count<N: Number>(values: &[N]) -> Result<N, Box<dyn Error>> {
+    N::parse_literal(&values.len().to_string())
+}
+
+fn total<N: Number>(values: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+    values.iter().try_fold(N::parse_literal("0")?, |total, value| total.add(value, settings))
+}
+
+fn mean<N: Number>(values: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+    if values.is_empty() {
+        return Err(Box::from(EmptyList { aggregate: "mean" }));
+    }
+    total(values, settings)?.divide(&count(values)?, settings)
+}
+
+/// The value `keep` prefers over all others, by `Number::compare`.
+fn extreme<N: Number>(values: &[N], keep: Ordering, aggregate: Aggregate, settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+    let (first, rest) = values.split_first().ok_or(EmptyList { aggregate: aggregate.get_name() })?;
+    rest.iter().try_fold(first.clone(), |extreme, value| match value.compare(&extreme, settings)? == keep {
+        true => Ok(value.clone()),
+        false => Ok(extreme),
+    })
+}
+
+fn median<N: Number>(values: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+    if values.is_empty() {
+        return Err(Box::from(EmptyList { aggregate: "median" }));
+    }
+    let mut error = None;
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|left, right| left.compare(right, settings).unwrap_or_else(|e| {
+        error.get_or_insert(e);
+        Ordering::Equal
+    }));
+    if let Some(error) = error {
+        return Err(error);
+    }
+    let middle = sorted.len() / 2;
+    match sorted.len() % 2 {
+        1 => Ok(sorted[middle].clone()),
+        _ => sorted[middle - 1].add(&sorted[middle], settings)?.divide(&N::parse_literal("2")?, settings),
+    }
+}
+
+fn stdev<N: Number>(values: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+    if values.len() < 2 {
+        return Err(Box::from(ArithmeticError { message: "Standard deviation needs at least two values" }));
+    }
+    let mean = mean(values, settings)?;
+    let squares = values.iter().map(|value| {
+        let deviation = value.subtract(&mean, settings)?;
+        deviation.multiply(&deviation, settings)
+    }).collect::<Result<Vec<N>, _>>()?;
+    let degrees = count(values)?.subtract(&N::parse_literal("1")?, settings)?;
+    total(&squares, settings)?.divide(&degrees, settings)?.apply_function(BuiltinFunction::Sqrt, settings)
+}
+
+impl Aggregate {
+    fn apply<N: Number>(&self, values: &[N], settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+        match self {
+            Aggregate::Sum => total(values, settings),
+            Aggregate::Mean => mean(values, settings),
+            Aggregate::Median => median(values, settings),
+            Aggregate::Stdev => stdev(values, settings),
+            Aggregate::Min => extreme(values, Ordering::Less, *self, settings),
+            Aggregate::Max => extreme(values, Ordering::Greater, *self, settings),
+            Aggregate::Len => count(values),
+        }
+    }
+}
+
+impl<N: Number> Expression<N> for ListLiteral<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.elements.iter().all(|element| element.can_evaluate(args)) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let values = self.elements.iter().map(|element| element.evaluate_value(args)).collect::<Result<Vec<_>, _>>()?;
+        match values.first() {
+            Some(Value::List(_)) => {
+                let rows = values.into_iter().map(|row| row.into_list()).collect::<Result<_, _>>()?;
+                Ok(Value::Matrix(Matrix::from_rows(rows).map_err(|e| locate_error(self, e))?))
+            }
+            _ => Ok(Value::List(values.into_iter().map(|value| value.into_number()).collect::<Result<_, _>>()?)),
+        }
+    }
+    fn to_string(&self) -> String {
+        let elements: Vec<String> = self.elements.iter().map(|element| element.to_string()).collect();
+        format!("[{}]", elements.join(", "))
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::List }
+    fn children(&self) -> Vec<&dyn Expression<N>> { self.elements.iter().map(|element| element.as_ref()).collect() }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Expression<N> for Index<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.evaluate(args).is_ok() }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        let values = self.list.evaluate_value(args)?.into_list()?;
+        let index = self.index.evaluate(args)?;
+        match index.to_index().and_then(|position| values.get(position)) {
+            Some(value) => Ok(value.clone()),
+            None => Err(Box::from(IndexOutOfRange { index: index.to_string(), length: values.len() })),
+        }
+    }
+    fn to_string(&self) -> String { format!("{}[{}]", self.list, self.index) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Index }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.list.as_ref(), self.index.as_ref()] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+/// Numbers among `values`, with lists and matrices contributing each of their elements.
+fn flatten<N: Number>(values: Vec<Value<N>>) -> Result<Vec<N>, Box<dyn Error>> {
+    let mut numbers = Vec::new();
+    for value in values {
+        match value {
+            Value::List(list) => numbers.extend(list),
+            Value::Matrix(matrix) => numbers.extend_from_slice(matrix.values()),
+            value => numbers.push(value.into_number()?),
+        }
+    }
+    Ok(numbers)
+}
+
+impl<N: Number> Expression<N> for Aggregation<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.arguments.iter().all(|argument| argument.can_evaluate(args)) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> {
+        let values = self.arguments.iter().map(|argument| argument.evaluate_value(args)).collect::<Result<Vec<_>, _>>()?;
+        match (self.aggregate, values.as_slice()) {
+            (Aggregate::Len, [Value::String(text)]) => N::parse_literal(&text.chars().count().to_string()),
+            _ => self.aggregate.apply(&flatten(values)?, &args.settings),
+        }
+    }
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> = self.arguments.iter().map(|argument| argument.to_string()).collect();
+        format!("{}({})", self.aggregate.get_name(), arguments.join(", "))
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Aggregation }
+    fn children(&self) -> Vec<&dyn Expression<N>> { self.arguments.iter().map(|argument| argument.as_ref()).collect() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
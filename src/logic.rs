@@ -0,0 +1,246 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use crate::enums::{ComparisonType, ExpressionType, OperatorType};
+use crate::errors::{MissingOperand, NoMatchingArm, Span};
+use crate::expression::{attach_to_operand, binary_attach_after, binary_attach_operator, binary_children, binary_is_complete, locate_error, Expression, ExpressionSettings, Scope};
+use crate::number::Number;
+use crate::value::Value;
+
+/// Comparison of two numbers, written `a < b`. Values within `f64_delta` of each other count as
+/// equal, for `<=` and `>=` as well as for `==` and `!=`. Two strings compare exactly, in
+/// lexicographic order.
+#[derive(Clone)]
+pub struct Comparison<N: Number = f64> {
+    pub comparison: ComparisonType,
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Logical conjunction; the right side is only evaluated when the left one is true.
+#[derive(Clone)]
+pub struct And<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Logical disjunction; the right side is only evaluated when the left one is false.
+#[derive(Clone)]
+pub struct Or<N: Number = f64> {
+    pub left: Box<dyn Expression<N>>,
+    pub right: Option<Box<dyn Expression<N>>>,
+    pub span: Option<Span>,
+}
+
+/// Logical negation, written `not a`. Binds looser than comparisons and tighter than `and`.
+#[derive(Clone)]
+pub struct Not<N: Number = f64> {
+    pub operand: Option<Box<dyn Expression<N>>>,
+}
+
+/// Conditional expression, written `if(condition, value, otherwise)`. Only the branch selected
+/// by the condition is evaluated, so an error in the other one does not matter.
+#[derive(Clone)]
+pub struct Conditional<N: Number = f64> {
+    pub condition: Box<dyn Expression<N>>,
+    pub value: Box<dyn Expression<N>>,
+    pub otherwise: Box<dyn Expression<N>>,
+}
+
+/// Condition of a piecewise arm, and the value it selects.
+pub type Arm<N = f64> = (Box<dyn Expression<N>>, Box<dyn Expression<N>>);
+
+/// Piecewise expression, written `piecewise(condition, value, ..., default)`. Evaluates to the
+/// value of the first arm whose condition holds, or to the optional default after the last arm.
+/// It is differentiated arm by arm, keeping the conditions; `autodiff` gradients follow the selected arm.
+#[derive(Clone)]
+pub struct Piecewise<N: Number = f64> {
+    pub arms: Vec<Arm<N>>,
+    pub default: Option<Box<dyn Expression<N>>>,
+}
+
+fn operand<'a, N: Number>(exp: &dyn Expression<N>, operand: &'a Option<Box<dyn Expression<N>>>) -> Result<&'a dyn Expression<N>, Box<dyn Error>> {
+    match operand {
+        None => Err(Box::from(MissingOperand { exp_type: exp.get_exp_type() })),
+        Some(exp_box) => Ok(exp_box.as_ref()),
+    }
+}
+
+fn compare<N: Number>(comparison: ComparisonType, left: &N, right: &N, settings: &ExpressionSettings) -> Result<bool, Box<dyn Error>> {
+    let ordering = match left.is_equal(right, settings)? {
+        true => Ordering::Equal,
+        false if comparison == ComparisonType::Equal => return Ok(false),
+        false if comparison == ComparisonType::NotEqual => return Ok(true),
+        false => left.compare(right, settings)?,
+    };
+    Ok(holds(comparison, ordering))
+}
+
+fn holds(comparison: ComparisonType, ordering: Ordering) -> bool {
+    match comparison {
+        ComparisonType::Less => ordering == Ordering::Less,
+        ComparisonType::LessOrEqual => ordering != Ordering::Greater,
+        ComparisonType::Greater => ordering == Ordering::Greater,
+        ComparisonType::GreaterOrEqual => ordering != Ordering::Less,
+        ComparisonType::Equal => ordering == Ordering::Equal,
+        ComparisonType::NotEqual => ordering != Ordering::Equal,
+    }
+}
+
+impl<N: Number> Expression<N> for Comparison<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let right = operand(self, &self.right)?;
+        match (self.left.evaluate_value(args)?, right.evaluate_value(args)?) {
+            (Value::String(left), right) => Ok(Value::Boolean(holds(self.comparison, left.cmp(&right.into_string()?)))),
+            (left, right) => {
+                let (left, right) = (left.into_number()?, right.into_number()?);
+                Ok(Value::Boolean(compare(self.comparison, &left, &right, &args.settings).map_err(|e| locate_error(self, e))?))
+            }
+        }
+    }
+    fn to_string(&self) -> String {
+        format!("{} {} {}", self.left, self.comparison.get_symbol(), self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Comparison }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Comparison { comparison: self.comparison, left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Comparison { comparison: self.comparison, left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
+
+impl<N: Number> Expression<N> for And<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let right = operand(self, &self.right)?;
+        Ok(Value::Boolean(self.left.evaluate_value(args)?.into_boolean()? && right.evaluate_value(args)?.into_boolean()?))
+    }
+    fn to_string(&self) -> String {
+        format!("{} and {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::And }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(And { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(And { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
+
+impl<N: Number> Expression<N> for Or<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let right = operand(self, &self.right)?;
+        Ok(Value::Boolean(self.left.evaluate_value(args)?.into_boolean()? || right.evaluate_value(args)?.into_boolean()?))
+    }
+    fn to_string(&self) -> String {
+        format!("{} or {}", self.left, self.right.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Or }
+    fn children(&self) -> Vec<&dyn Expression<N>> { binary_children(self.left.as_ref(), &self.right) }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.right, |right| Box::from(Or { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.right) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.right, operator, span, |right| Box::from(Or { left: self.left.clone(), right: Some(right), span: self.span.clone() }))
+    }
+}
+
+impl<N: Number> Expression<N> for Not<N> {
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        Ok(Value::Boolean(!operand(self, &self.operand)?.evaluate_value(args)?.into_boolean()?))
+    }
+    fn to_string(&self) -> String {
+        format!("not {}", self.operand.as_ref().unwrap().clone_box())
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Not }
+    fn children(&self) -> Vec<&dyn Expression<N>> {
+        self.operand.iter().map(|exp_box| exp_box.as_ref()).collect()
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_after(exp, &self.operand, |operand| Box::from(Not { operand: Some(operand) }))
+    }
+    fn is_complete(&self) -> bool { binary_is_complete(&self.operand) }
+    fn attach_operator(&self, operator: OperatorType, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        binary_attach_operator(self, &self.operand, operator, span, |operand| Box::from(Not { operand: Some(operand) }))
+    }
+}
+
+impl<N: Number> Conditional<N> {
+    fn branch(&self, args: &Scope<N>) -> Result<&dyn Expression<N>, Box<dyn Error>> {
+        match self.condition.evaluate_value(args)?.into_boolean()? {
+            true => Ok(self.value.as_ref()),
+            false => Ok(self.otherwise.as_ref()),
+        }
+    }
+}
+
+impl<N: Number> Expression<N> for Conditional<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        self.condition.can_evaluate(args) && self.branch(args).is_ok_and(|branch| branch.can_evaluate(args))
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.branch(args)?.evaluate(args) }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.branch(args)?.evaluate_value(args) }
+    fn to_string(&self) -> String { format!("if({}, {}, {})", self.condition, self.value, self.otherwise) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Conditional }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.condition.as_ref(), self.value.as_ref(), self.otherwise.as_ref()] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> Piecewise<N> {
+    /// Value of the first arm whose condition holds; later conditions are not evaluated.
+    fn branch(&self, args: &Scope<N>) -> Result<&dyn Expression<N>, Box<dyn Error>> {
+        for (condition, value) in &self.arms {
+            if condition.evaluate_value(args)?.into_boolean()? {
+                return Ok(value.as_ref());
+            }
+        }
+        match &self.default {
+            None => Err(Box::from(NoMatchingArm { expression: Expression::to_string(self) })),
+            Some(default) => Ok(default.as_ref()),
+        }
+    }
+}
+
+impl<N: Number> Expression<N> for Piecewise<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool {
+        self.branch(args).is_ok_and(|branch| branch.can_evaluate(args))
+    }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.branch(args)?.evaluate(args) }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.branch(args)?.evaluate_value(args) }
+    fn to_string(&self) -> String {
+        let mut parts: Vec<String> = self.arms.iter().map(|(condition, value)| format!("{}, {}", condition, value)).collect();
+        parts.extend(self.default.iter().map(|default| default.to_string()));
+        format!("piecewise({})", parts.join(", "))
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::Piecewise }
+    fn children(&self) -> Vec<&dyn Expression<N>> {
+        let mut children: Vec<&dyn Expression<N>> = self.arms.iter().flat_map(|(condition, value)| [condition.as_ref(), value.as_ref()]).collect();
+        children.extend(self.default.iter().map(|default| default.as_ref()));
+        children
+    }
+    fn differentiate(&self, variable: &str) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        let arms = self.arms.iter()
+            .map(|(condition, value)| Ok((condition.clone(), value.differentiate(variable)?)))
+            .collect::<Result<Vec<Arm<N>>, Box<dyn Error>>>()?;
+        let default = self.default.as_ref().map(|default| default.differentiate(variable)).transpose()?;
+        Ok(Box::from(Piecewise { arms, default }))
+    }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
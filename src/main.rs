@@ -11,7 +11,10 @@ fn main() {
         Ok(expression_box) => {
             let expression = expression_box.as_ref();
             println!("EX: {}", expression);
-            println!("VAL: {}", expression.evaluate(&expression_args));
+            match expression.evaluate(&expression_args) {
+                Ok(value) => println!("VAL: {}", value),
+                Err(error_box) => println!("Error: {}", error_box),
+            }
         },
         Err(error_box) => {
             let error = error_box.as_ref();
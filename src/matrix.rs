@@ -0,0 +1,223 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::enums::{BuiltinFunction, ExpressionType, MatrixFunction};
+use crate::errors::{ArithmeticError, NotSquare, ShapeMismatch, Span};
+use crate::expression::{attach_to_operand, locate_error, Expression, ExpressionSettings, Scope};
+use crate::number::Number;
+use crate::value::Value;
+
+/// Matrix of numbers, stored row by row. Written `[[1, 2], [3, 4]]` in expressions, where `*`
+/// is the matrix product and other arithmetic applies element by element.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Matrix<N: Number = f64> {
+    rows: usize,
+    columns: usize,
+    values: Vec<N>,
+}
+
+/// Call of a matrix function, written `transpose(m)`, `det(m)` or `inv(m)`.
+#[derive(Clone)]
+pub struct MatrixOperation<N: Number = f64> {
+    pub function: MatrixFunction,
+    pub argument: Box<dyn Expression<N>>,
+    pub span: Option<Span>,
+}
+
+fn swap_rows<N: Number>(values: &mut [N], width: usize, first: usize, second: usize) {
+    for column in 0..width {
+        values.swap(first * width + column, second * width + column);
+    }
+}
+
+/// Row from `column` down with the entry of largest magnitude in `column`, for partial pivoting.
+fn pivot_row<N: Number>(values: &[N], width: usize, rows: usize, column: usize, settings: &ExpressionSettings) -> Result<usize, Box<dyn Error>> {
+    let mut pivot = column;
+    let mut largest = values[column * width + column].apply_function(BuiltinFunction::Abs, settings)?;
+    for row in column + 1..rows {
+        let magnitude = values[row * width + column].apply_function(BuiltinFunction::Abs, settings)?;
+        if magnitude.compare(&largest, settings)? == Ordering::Greater {
+            pivot = row;
+            largest = magnitude;
+        }
+    }
+    Ok(pivot)
+}
+
+impl<N: Number> Matrix<N> {
+    /// Creates a matrix from its values, row by row.
+    pub fn new(rows: usize, columns: usize, values: Vec<N>) -> Result<Matrix<N>, Box<dyn Error>> {
+        if values.len() != rows * columns {
+            return Err(Box::from(ShapeMismatch { left: format!("{}x{} matrix", rows, columns), right: format!("list of {}", values.len()), expression: None, span: None }));
+        }
+        Ok(Matrix { rows, columns, values })
+    }
+
+    /// Creates a matrix from rows, which must all have the same length.
+    pub fn from_rows(rows: Vec<Vec<N>>) -> Result<Matrix<N>, Box<dyn Error>> {
+        let columns = rows.first().map_or(0, |row| row.len());
+        if let Some(row) = rows.iter().find(|row| row.len() != columns) {
+            return Err(Box::from(ShapeMismatch { left: format!("list of {}", columns), right: format!("list of {}", row.len()), expression: None, span: None }));
+        }
+        Ok(Matrix { rows: rows.len(), columns, values: rows.into_iter().flatten().collect() })
+    }
+
+    fn identity(size: usize) -> Result<Matrix<N>, Box<dyn Error>> {
+        let (zero, one) = (N::parse_literal("0")?, N::parse_literal("1")?);
+        let values = (0..size * size).map(|index| if index % (size + 1) == 0 { one.clone() } else { zero.clone() }).collect();
+        Ok(Matrix { rows: size, columns: size, values })
+    }
+
+    pub fn rows(&self) -> usize { self.rows }
+
+    pub fn columns(&self) -> usize { self.columns }
+
+    /// Values row by row.
+    pub fn values(&self) -> &[N] { &self.values }
+
+    pub fn get(&self, row: usize, column: usize) -> Option<&N> {
+        match row < self.rows && column < self.columns {
+            true => self.values.get(row * self.columns + column),
+            false => None,
+        }
+    }
+
+    pub(crate) fn shape(&self) -> String { format!("{}x{} matrix", self.rows, self.columns) }
+
+    fn require_square(&self) -> Result<(), Box<dyn Error>> {
+        match self.rows == self.columns {
+            true => Ok(()),
+            false => Err(Box::from(NotSquare { shape: self.shape(), expression: None, span: None })),
+        }
+    }
+
+    pub(crate) fn map(&self, operation: impl Fn(&N) -> Result<N, Box<dyn Error>>) -> Result<Matrix<N>, Box<dyn Error>> {
+        Ok(Matrix { rows: self.rows, columns: self.columns, values: self.values.iter().map(operation).collect::<Result<_, _>>()? })
+    }
+
+    /// Applies `operation` to the elements at the same positions of two matrices of the same shape.
+    pub(crate) fn zip_with(&self, other: &Matrix<N>, operation: impl Fn(&N, &N) -> Result<N, Box<dyn Error>>) -> Result<Matrix<N>, Box<dyn Error>> {
+        if (self.rows, self.columns) != (other.rows, other.columns) {
+            return Err(Box::from(ShapeMismatch { left: self.shape(), right: other.shape(), expression: None, span: None }));
+        }
+        let values = self.values.iter().zip(&other.values).map(|(left, right)| operation(left, right)).collect::<Result<_, _>>()?;
+        Ok(Matrix { rows: self.rows, columns: self.columns, values })
+    }
+
+    pub fn transpose(&self) -> Matrix<N> {
+        let values = (0..self.rows * self.columns).map(|index| self.values[(index % self.rows) * self.columns + index / self.rows].clone()).collect();
+        Matrix { rows: self.columns, columns: self.rows, values }
+    }
+
+    /// Matrix product; the columns of `self` must match the rows of `other`.
+    pub fn multiply(&self, other: &Matrix<N>, settings: &ExpressionSettings) -> Result<Matrix<N>, Box<dyn Error>> {
+        if self.columns != other.rows {
+            return Err(Box::from(ShapeMismatch { left: self.shape(), right: other.shape(), expression: None, span: None }));
+        }
+        let mut values = Vec::with_capacity(self.rows * other.columns);
+        for row in 0..self.rows {
+            for column in 0..other.columns {
+                let mut total = N::parse_literal("0")?;
+                for inner in 0..self.columns {
+                    total = total.add(&self.values[row * self.columns + inner].multiply(&other.values[inner * other.columns + column], settings)?, settings)?;
+                }
+                values.push(total);
+            }
+        }
+        Ok(Matrix { rows: self.rows, columns: other.columns, values })
+    }
+
+    /// Determinant by Gaussian elimination with partial pivoting.
+    pub fn determinant(&self, settings: &ExpressionSettings) -> Result<N, Box<dyn Error>> {
+        self.require_square()?;
+        let size = self.rows;
+        let mut values = self.values.clone();
+        let mut determinant = N::parse_literal("1")?;
+        for column in 0..size {
+            let pivot = pivot_row(&values, size, size, column, settings)?;
+            if values[pivot * size + column].is_zero(settings.f64_delta) {
+                return N::parse_literal("0");
+            }
+            if pivot != column {
+                swap_rows(&mut values, size, pivot, column);
+                determinant = N::parse_literal("0")?.subtract(&determinant, settings)?;
+            }
+            let diagonal = values[column * size + column].clone();
+            for row in column + 1..size {
+                let factor = values[row * size + column].divide(&diagonal, settings)?;
+                for inner in column..size {
+                    let reduced = values[row * size + inner].subtract(&factor.multiply(&values[column * size + inner], settings)?, settings)?;
+                    values[row * size + inner] = reduced;
+                }
+            }
+            determinant = determinant.multiply(&diagonal, settings)?;
+        }
+        Ok(determinant)
+    }
+
+    /// Inverse by Gauss-Jordan elimination with partial pivoting. A pivot within `f64_delta`
+    /// of zero makes the matrix singular.
+    pub fn inverse(&self, settings: &ExpressionSettings) -> Result<Matrix<N>, Box<dyn Error>> {
+        self.require_square()?;
+        let size = self.rows;
+        let width = 2 * size;
+        let identity = Matrix::identity(size)?;
+        let mut values: Vec<N> = (0..size)
+            .flat_map(|row| self.values[row * size..(row + 1) * size].iter().chain(&identity.values[row * size..(row + 1) * size]).cloned())
+            .collect();
+        for column in 0..size {
+            let pivot = pivot_row(&values, width, size, column, settings)?;
+            if values[pivot * width + column].is_zero(settings.f64_delta) {
+                return Err(Box::from(ArithmeticError { message: "Matrix is singular" }));
+            }
+            swap_rows(&mut values, width, pivot, column);
+            let diagonal = values[column * width + column].clone();
+            for inner in 0..width {
+                values[column * width + inner] = values[column * width + inner].divide(&diagonal, settings)?;
+            }
+            for row in (0..size).filter(|row| *row != column) {
+                let factor = values[row * width + column].clone();
+                for inner in 0..width {
+                    let reduced = values[row * width + inner].subtract(&factor.multiply(&values[column * width + inner], settings)?, settings)?;
+                    values[row * width + inner] = reduced;
+                }
+            }
+        }
+        let values = (0..size).flat_map(|row| values[row * width + size..(row + 1) * width].to_vec()).collect();
+        Ok(Matrix { rows: size, columns: size, values })
+    }
+}
+
+impl<N: Number> Display for Matrix<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let rows: Vec<String> = self.values.chunks(self.columns.max(1)).map(|row| {
+            let row: Vec<String> = row.iter().map(|value| value.to_string()).collect();
+            format!("[{}]", row.join(", "))
+        }).collect();
+        write!(f, "[{}]", rows.join(", "))
+    }
+}
+
+impl<N: Number> MatrixOperation<N> {
+    fn apply(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> {
+        let matrix = self.argument.evaluate_value(args)?.into_matrix()?;
+        match self.function {
+            MatrixFunction::Transpose => Ok(Value::Matrix(matrix.transpose())),
+            MatrixFunction::Determinant => Ok(Value::Number(matrix.determinant(&args.settings)?)),
+            MatrixFunction::Inverse => Ok(Value::Matrix(matrix.inverse(&args.settings)?)),
+        }
+    }
+}
+
+impl<N: Number> Expression<N> for MatrixOperation<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.argument.can_evaluate(args) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { self.apply(args).map_err(|e| locate_error(self, e)) }
+    fn to_string(&self) -> String { format!("{}({})", self.function.get_name(), self.argument) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::MatrixOperation }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![self.argument.as_ref()] }
+    fn span(&self) -> Option<Span> { self.span.clone() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
@@ -0,0 +1,249 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Debug, Display};
+use crate::enums::{BuiltinFunction, IntegerDivision, NonFinitePolicy, OperatorType};
+use crate::errors::{ArithmeticError, NotCompilable, ParsingError, UnsupportedOperation};
+use crate::expression::ExpressionSettings;
+
+pub(crate) type BinaryOperation<N> = fn(&N, &N, &ExpressionSettings) -> Result<N, Box<dyn Error>>;
+
+/// Numeric type an expression tree is parsed into and evaluated with.
+pub trait Number: Clone + Debug + Display + PartialEq + Send + Sync + 'static {
+    /// Whether literals may have a fractional part; the parser rejects them otherwise.
+    const FRACTIONAL: bool = true;
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>>;
+    /// Value of a named constant such as the imaginary unit, if the type has one by that name.
+    fn parse_constant(_name: &str) -> Option<Self> { None }
+    /// The value as it is written in a formula, so that printing and parsing again gives the
+    /// same expression. Values whose printed form `parse_literal` does not read back, such as
+    /// the fraction `1/2`, are put in brackets.
+    fn to_literal(&self) -> String {
+        let text = self.to_string();
+        match Self::parse_literal(&text) {
+            Ok(value) if value == *self => text,
+            _ => format!("({})", text),
+        }
+    }
+    fn add(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>>;
+    fn subtract(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>>;
+    fn multiply(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>>;
+    fn divide(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>>;
+    fn remainder(&self, _other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: "%" }))
+    }
+    fn power(&self, _exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: "^" }))
+    }
+    /// Attaches a standard uncertainty to the value, for the `±` operator.
+    fn with_uncertainty(&self, _uncertainty: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: "±" }))
+    }
+    /// Expresses the value in the unit of `target`, for the `to` operator.
+    fn convert(&self, _target: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: "to" }))
+    }
+    fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: function.get_name() }))
+    }
+    /// Orders two values for `<`, `<=`, `>` and `>=`. Equality is checked with `is_equal`
+    /// before this is called.
+    fn compare(&self, _other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+        Err(Box::from(UnsupportedOperation { name: "comparison" }))
+    }
+    /// Whether two values are equal, within `f64_delta` for inexact types. Checks whether their
+    /// difference `is_zero`; exact types override this to compare without arithmetic that could overflow.
+    fn is_equal(&self, other: &Self, settings: &ExpressionSettings) -> Result<bool, Box<dyn Error>> {
+        // the difference is only used for the tolerance, so it must not be replaced by the non-finite policy
+        let exact = ExpressionSettings { non_finite: NonFinitePolicy::Propagate, ..settings.clone() };
+        Ok(self.subtract(other, &exact)?.is_zero(settings.f64_delta))
+    }
+    /// Whether the value should be treated as zero, `delta` being the tolerance for inexact types.
+    fn is_zero(&self, delta: f64) -> bool;
+    /// The value as a list position, if it is a whole number that is not negative.
+    fn to_index(&self) -> Option<usize>;
+    /// Applies an arithmetic operator to `left` and `right` element by element, for batch
+    /// evaluation. Types whose operations are plain machine arithmetic override this with loops
+    /// the compiler can vectorise.
+    fn apply_batch(operator: OperatorType, left: &[Self], right: &[Self], output: &mut [Self], settings: &ExpressionSettings) -> Result<(), Box<dyn Error>> {
+        apply_batch_by_element(operator, left, right, output, settings)
+    }
+}
+
+/// Scalar operation behind an arithmetic operator.
+fn binary_operation<N: Number>(operator: OperatorType) -> Result<BinaryOperation<N>, Box<dyn Error>> {
+    match operator {
+        OperatorType::Add => Ok(N::add),
+        OperatorType::Subtract => Ok(N::subtract),
+        OperatorType::Multiply | OperatorType::ImplicitMultiply => Ok(N::multiply),
+        OperatorType::Divide => Ok(N::divide),
+        OperatorType::Modulo => Ok(N::remainder),
+        OperatorType::Power => Ok(N::power),
+        OperatorType::PlusMinus => Ok(N::with_uncertainty),
+        OperatorType::Convert => Ok(N::convert),
+        OperatorType::Compare(_) | OperatorType::And | OperatorType::Or => Err(Box::from(NotCompilable { exp_type: operator.get_exp_type() })),
+    }
+}
+
+fn apply_batch_by_element<N: Number>(operator: OperatorType, left: &[N], right: &[N], output: &mut [N], settings: &ExpressionSettings) -> Result<(), Box<dyn Error>> {
+    let operation = binary_operation::<N>(operator)?;
+    for (result, (left, right)) in output.iter_mut().zip(left.iter().zip(right)) {
+        *result = operation(left, right, settings)?;
+    }
+    Ok(())
+}
+
+/// Applies `settings.non_finite` to a NaN or infinite result. Results computed from operands
+/// that were already NaN or infinite are returned unchanged.
+fn finite_or_policy(result: f64, operands_finite: bool, cause: Option<&'static str>, settings: &ExpressionSettings) -> Result<f64, Box<dyn Error>> {
+    if result.is_finite() || !operands_finite {
+        return Ok(result);
+    }
+    let cause = cause.unwrap_or(if result.is_nan() { "Result is not a number" } else { "Overflow to infinity" });
+    match settings.non_finite {
+        NonFinitePolicy::Error => Err(Box::from(ArithmeticError { message: cause })),
+        NonFinitePolicy::Propagate => Ok(result),
+        NonFinitePolicy::Substitute(value) => Ok(value),
+    }
+}
+
+macro_rules! impl_float_number {
+    ($float:ty) => {
+        impl Number for $float {
+            fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> { Ok(literal.parse::<$float>()?) }
+            fn to_index(&self) -> Option<usize> {
+                (self.fract() == 0.0 && *self >= 0.0 && *self < usize::MAX as $float).then_some(*self as usize)
+            }
+            fn add(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(finite_or_policy((self + other) as f64, self.is_finite() && other.is_finite(), None, settings)? as $float)
+            }
+            fn subtract(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(finite_or_policy((self - other) as f64, self.is_finite() && other.is_finite(), None, settings)? as $float)
+            }
+            fn multiply(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(finite_or_policy((self * other) as f64, self.is_finite() && other.is_finite(), None, settings)? as $float)
+            }
+            fn divide(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                let cause = if *other == 0.0 { Some("Division by zero") } else { None };
+                Ok(finite_or_policy((self / other) as f64, self.is_finite() && other.is_finite(), cause, settings)? as $float)
+            }
+            fn remainder(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                let cause = if *other == 0.0 { Some("Division by zero") } else { None };
+                Ok(finite_or_policy((self % other) as f64, self.is_finite() && other.is_finite(), cause, settings)? as $float)
+            }
+            fn power(&self, exponent: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(finite_or_policy(self.powf(*exponent) as f64, self.is_finite() && exponent.is_finite(), None, settings)? as $float)
+            }
+            fn apply_function(&self, function: BuiltinFunction, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                let (result, cause) = match function {
+                    BuiltinFunction::Sqrt => (self.sqrt(), Some("Square root of a negative number")),
+                    BuiltinFunction::Exp => (self.exp(), None),
+                    BuiltinFunction::Ln if *self == 0.0 => (self.ln(), Some("Logarithm of zero")),
+                    BuiltinFunction::Ln => (self.ln(), Some("Logarithm of a negative number")),
+                    BuiltinFunction::Sin => (self.sin(), None),
+                    BuiltinFunction::Cos => (self.cos(), None),
+                    BuiltinFunction::Tan => (self.tan(), None),
+                    BuiltinFunction::Abs => (self.abs(), None),
+                };
+                Ok(finite_or_policy(result as f64, self.is_finite(), cause, settings)? as $float)
+            }
+            fn compare(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+                self.partial_cmp(other).ok_or_else(|| Box::from(ArithmeticError { message: "NaN cannot be compared" }))
+            }
+            fn is_zero(&self, delta: f64) -> bool { (self.abs() as f64) <= delta }
+            /// Computes every result first and applies `settings.non_finite` in a second pass,
+            /// keeping the arithmetic loops free of branches.
+            fn apply_batch(operator: OperatorType, left: &[Self], right: &[Self], output: &mut [Self], settings: &ExpressionSettings) -> Result<(), Box<dyn Error>> {
+                let operands = left.iter().zip(right);
+                match operator {
+                    OperatorType::Add => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left + right },
+                    OperatorType::Subtract => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left - right },
+                    OperatorType::Multiply | OperatorType::ImplicitMultiply => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left * right },
+                    OperatorType::Divide => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left / right },
+                    OperatorType::Modulo => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left % right },
+                    OperatorType::Power => for (result, (left, right)) in output.iter_mut().zip(operands) { *result = left.powf(*right) },
+                    _ => return apply_batch_by_element(operator, left, right, output, settings),
+                }
+                if matches!(settings.non_finite, NonFinitePolicy::Propagate) {
+                    return Ok(());
+                }
+                let division = matches!(operator, OperatorType::Divide | OperatorType::Modulo);
+                for (result, (left, right)) in output.iter_mut().zip(left.iter().zip(right)) {
+                    if !result.is_finite() {
+                        let cause = if division && *right == 0.0 { Some("Division by zero") } else { None };
+                        *result = finite_or_policy(*result as f64, left.is_finite() && right.is_finite(), cause, settings)? as $float;
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+impl_float_number!(f64);
+impl_float_number!(f32);
+
+const INTEGER_OVERFLOW: ArithmeticError = ArithmeticError { message: "Integer overflow" };
+const DIVISION_BY_ZERO: ArithmeticError = ArithmeticError { message: "Division by zero" };
+
+/// Checked integer arithmetic: overflow and division by zero are errors instead of wrapping or panicking.
+macro_rules! impl_integer_number {
+    ($integer:ty) => {
+        impl Number for $integer {
+            const FRACTIONAL: bool = false;
+            fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+                if literal.contains('.') {
+                    return Err(Box::from(ParsingError { message: "Fractional literal in integer mode" }));
+                }
+                Ok(literal.parse::<$integer>()?)
+            }
+            fn to_index(&self) -> Option<usize> { usize::try_from(*self).ok() }
+            fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(self.checked_add(*other).ok_or(INTEGER_OVERFLOW)?)
+            }
+            fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(self.checked_sub(*other).ok_or(INTEGER_OVERFLOW)?)
+            }
+            fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                Ok(self.checked_mul(*other).ok_or(INTEGER_OVERFLOW)?)
+            }
+            fn divide(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                if *other == 0 {
+                    return Err(Box::from(DIVISION_BY_ZERO));
+                }
+                let quotient = self.checked_div(*other).ok_or(INTEGER_OVERFLOW)?;
+                let inexact = self % other != 0;
+                match settings.integer_division {
+                    IntegerDivision::Floor if inexact && (*self < 0) != (*other < 0) => Ok(quotient - 1),
+                    _ => Ok(quotient),
+                }
+            }
+            fn remainder(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                if *other == 0 {
+                    return Err(Box::from(DIVISION_BY_ZERO));
+                }
+                // the remainder of MIN / -1 is zero, even though the quotient overflows
+                let remainder = self.checked_rem(*other).unwrap_or(0);
+                match settings.integer_division {
+                    IntegerDivision::Floor if remainder != 0 && (remainder < 0) != (*other < 0) => Ok(remainder + other),
+                    _ => Ok(remainder),
+                }
+            }
+            fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                let exponent = u32::try_from(*exponent).map_err(|_| ArithmeticError { message: "Integer exponent must be non-negative" })?;
+                Ok(self.checked_pow(exponent).ok_or(INTEGER_OVERFLOW)?)
+            }
+            fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+                match function {
+                    BuiltinFunction::Abs => Ok(self.checked_abs().ok_or(INTEGER_OVERFLOW)?),
+                    _ => Err(Box::from(UnsupportedOperation { name: function.get_name() })),
+                }
+            }
+            fn compare(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> { Ok(self.cmp(other)) }
+            fn is_equal(&self, other: &Self, _settings: &ExpressionSettings) -> Result<bool, Box<dyn Error>> { Ok(self == other) }
+            fn is_zero(&self, _delta: f64) -> bool { *self == 0 }
+        }
+    };
+}
+
+impl_integer_number!(i64);
+impl_integer_number!(i128);
@@ -1,51 +1,168 @@
 use std::error::Error;
 use std::fmt::{Debug, Formatter};
-use crate::enums::{BufferState, CharType, OperatorType};
-use crate::errors::{EmptyBuffer, InvalidCharacter, ParsingError};
-use crate::expression::{Expression, ScalarValue, Subtraction, Addition, Multiplication, Division};
+use crate::binding::{Let, Product, Summation};
+use crate::enums::{Aggregate, BufferState, CharType, MatrixFunction, OperatorType, TextFunction};
+use crate::errors::{ArgumentCount, EmptyBuffer, InvalidCharacter, ParsingError, Span};
+use crate::expression::{Expression, ScalarValue, Constant, Variable, Bracket, Function};
+use crate::list::{Aggregation, Index, ListLiteral};
+use crate::logic::{Conditional, Not, Piecewise};
+use crate::matrix::MatrixOperation;
+use crate::number::Number;
+use crate::text::{StringLiteral, TextOperation};
 
-struct ParserContext {
+type Arguments<N> = Vec<Box<dyn Expression<N>>>;
+
+struct ParserContext<N: Number> {
     buffer: String,
     state: BufferState,
-    expression: Option<Box<dyn Expression>>,
+    expression: Option<Box<dyn Expression<N>>>,
+    /// Name of the function whose argument, or of the list whose index, is being read in the `Bracket` state.
+    function: Option<String>,
+    /// The bracket that opened the `Bracket` state, `(` or `[`.
+    bracket: char,
+    /// Number of nested brackets opened inside the `Bracket` state.
+    depth: usize,
+    /// String literals inside the `Bracket` state, whose brackets do not count.
+    quotes: Quotes,
+    /// Position of the first character of the token in the buffer; for a call, that of its name.
+    start: usize,
+    /// Position of the first character inside the brackets in the `Bracket` state.
+    contents: usize,
+    /// Position of the character being read. Positions count characters from the start of the
+    /// whole input, also in text inside brackets.
+    index: usize,
+}
+
+/// Tracks whether a scan over raw text is inside a string literal.
+#[derive(Clone, Copy, Default)]
+struct Quotes {
+    quoted: bool,
+    escaped: bool,
+}
+
+impl Quotes {
+    /// Moves past `character` and tells whether it stands outside string literals, quotes excluded.
+    fn outside(&mut self, character: char) -> bool {
+        match (self.quoted, self.escaped, character) {
+            (true, true, _) => {
+                self.escaped = false;
+                false
+            }
+            (true, false, '\\') => {
+                self.escaped = true;
+                false
+            }
+            (quoted, false, '"') => {
+                self.quoted = !quoted;
+                false
+            }
+            (quoted, _, _) => !quoted,
+        }
+    }
 }
 
-impl Debug for ParserContext {
+impl<N: Number> Debug for ParserContext<N> {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         let exp_repr = if let Some(exp_box) = &self.expression { exp_box.as_ref().to_string() } else { String::from("") };
         write!(f, "{{ buffer = {:?}, state = {:?}, exp = {:?} }}", self.buffer, self.state, exp_repr)
     }
 }
 
-impl ParserContext {
-    fn attach_exp(&mut self, exp: &Box<dyn Expression>) -> Result<(), Box<dyn Error>> {
+impl<N: Number> ParserContext<N> {
+    fn attach_exp(&mut self, exp: &dyn Expression<N>) -> Result<(), Box<dyn Error>> {
         match &mut self.expression {
             None => self.expression = Some(exp.clone_box()),
             Some(exp_box) => { self.expression = Some(exp_box.as_ref().attach_after(exp)?) }
         }
         Ok(())
     }
+
+    fn follows_complete_expression(&self) -> bool {
+        self.expression.as_ref().is_some_and(|exp| exp.is_complete())
+    }
+
+    /// Applies an operator read from the buffer, or implied by it, at the position of the buffer.
+    fn attach_operator(&mut self, operator: OperatorType) -> Result<(), Box<dyn Error>> {
+        let span = self.span_to(self.start + self.buffer.chars().count());
+        if let Some(exp_box) = &self.expression {
+            self.expression = Some(exp_box.as_ref().attach_operator(operator, span)?);
+        }
+        Ok(())
+    }
+
+    /// Span from the start of the token to `end`.
+    fn span_to(&self, end: usize) -> Option<Span> {
+        Some(self.start..end)
+    }
 }
 
-fn parse_buffer(context: &mut ParserContext) -> Result<(), Box<dyn Error>> {
+fn parse_buffer<N: Number>(context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    let keyword = OperatorType::parse_keyword(&context.buffer).filter(|_| context.follows_complete_expression());
+    let contents = context.contents;
     let result = match context.state {
         BufferState::Empty => return Err(Box::from(ParsingError { message: "Empty buffer!" })),
         BufferState::Number => {
-            let result = context.buffer.parse::<f64>();
+            let result = N::parse_literal(&context.buffer);
             match result {
-                Ok(v) => Ok(Box::from(ScalarValue { value: v }) as Box<dyn Expression>),
-                Err(e) => Err(format!("Error while parsing number: {}", e.to_string()))
+                Ok(v) => Ok(Box::from(ScalarValue { value: v }) as Box<dyn Expression<N>>),
+                Err(e) => Err(format!("Error while parsing number: {}", e))
+            }
+        }
+        BufferState::Name if keyword.is_some() => {
+            context.attach_operator(keyword.unwrap())?;
+            context.buffer = String::new();
+            context.state = BufferState::Empty;
+            return Ok(());
+        }
+        BufferState::Name if context.buffer == "not" => Ok(Box::from(Not { operand: None }) as Box<dyn Expression<N>>),
+        BufferState::Name => match N::parse_constant(&context.buffer) {
+            // a constant right after a value multiplies it, so `5 m` reads as five metres
+            Some(value) => {
+                if context.follows_complete_expression() {
+                    context.attach_operator(OperatorType::ImplicitMultiply)?;
+                }
+                Ok(Box::from(Constant { name: context.buffer.clone(), value }) as Box<dyn Expression<N>>)
+            }
+            None => Ok(Box::from(Variable { name: context.buffer.clone() }) as Box<dyn Expression<N>>),
+        },
+        BufferState::Operator => {
+            let operator = OperatorType::parse_operator_type(&context.buffer).ok_or(ParsingError { message: "Unknown operator" })?;
+            context.attach_operator(operator)?;
+            context.buffer = String::new();
+            context.state = BufferState::Empty;
+            return Ok(());
+        }
+        BufferState::Text | BufferState::Escape => Ok(Box::from(StringLiteral { value: context.buffer.clone() }) as Box<dyn Expression<N>>),
+        BufferState::Bracket if context.bracket == '[' => match context.function.take() {
+            None => Ok(Box::from(ListLiteral { elements: parse_arguments(&context.buffer, contents)?, span: context.span_to(context.index + 1) }) as Box<dyn Expression<N>>),
+            Some(name) => Ok(Box::from(Index { list: Box::from(Variable { name }), index: parse_at::<N>(&context.buffer, contents)? }) as Box<dyn Expression<N>>),
+        },
+        BufferState::Bracket => {
+            match context.function.take() {
+                None => Ok(Box::from(Bracket { inner: parse_at::<N>(&context.buffer, contents)? }) as Box<dyn Expression<N>>),
+                Some(name) if name == "if" => Ok(parse_conditional(&context.buffer, contents)?),
+                Some(name) if name == "piecewise" => Ok(parse_piecewise(&context.buffer, contents)?),
+                Some(name) if is_series(&name) || (name == "sum" && is_range_sum(&context.buffer)) => Ok(parse_series(&name, &context.buffer, contents)?),
+                Some(name) if MatrixFunction::parse_matrix_function(&name).is_some() => {
+                    Ok(parse_matrix_operation(&name, &context.buffer, contents, context.span_to(context.index + 1))?)
+                }
+                Some(name) if TextFunction::parse_text_function(&name).is_some() => Ok(parse_text_operation(&name, &context.buffer, contents)?),
+                Some(name) if Aggregate::parse_aggregate(&name).is_some() => Ok(Box::from(Aggregation {
+                    aggregate: Aggregate::parse_aggregate(&name).unwrap(),
+                    arguments: parse_arguments(&context.buffer, contents)?,
+                }) as Box<dyn Expression<N>>),
+                Some(name) => Ok(Box::from(Function { name, arguments: parse_arguments(&context.buffer, contents)? }) as Box<dyn Expression<N>>),
             }
         }
-        _ => todo!(),
     }?;
-    context.attach_exp(&result)?;
+    context.attach_exp(result.as_ref())?;
     context.buffer = String::new();
     context.state = BufferState::Empty;
     Ok(())
 }
 
-fn parse_empty(character: char, char_type: CharType, index: usize, context: &mut ParserContext) -> Result<(), Box<dyn Error>> {
+fn parse_empty<N: Number>(character: char, char_type: CharType, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    context.start = index;
     match char_type {
         CharType::Number => {
             context.state = BufferState::Number;
@@ -56,39 +173,34 @@ fn parse_empty(character: char, char_type: CharType, index: usize, context: &mut
             context.buffer.push(character)
         }
         CharType::Operator => {
-            let operator_type = OperatorType::parse_operator_type(character);
-            match context.expression {
-                Some(ref exp) => {
-                    match operator_type {
-                        OperatorType::Add => context.expression = Some(Box::from(Addition { left: exp.clone_box(), right: None })),
-                        OperatorType::Subtract => context.expression = Some(Box::from(Subtraction { left: exp.clone_box(), right: None })),
-                        OperatorType::Multiply => context.expression = Some(Box::from(Multiplication { left: exp.clone_box(), right: None })),
-                        OperatorType::Divide => context.expression = Some(Box::from(Division { left: exp.clone_box(), right: None })),
-                        OperatorType::Power => todo!(),
-                    }
+            match &context.expression {
+                Some(_) if context.follows_complete_expression() => {
+                    context.state = BufferState::Operator;
+                    context.buffer.push(character)
+                }
+                _ if character == '-' => {
+                    context.state = BufferState::Number;
+                    context.buffer.push(character)
                 }
-                None => {
-                    match operator_type {
-                        OperatorType::Subtract => {
-                            context.state = BufferState::Number;
-                            context.buffer.push(character)
-                        },
-                        _ => return Err(Box::from(InvalidCharacter { character, index, message: "Operator at the start of a block" })),
-                    }
-                },
+                Some(_) => return Err(Box::from(InvalidCharacter { character, index, message: "Operator after operator" })),
+                None => return Err(Box::from(InvalidCharacter { character, index, message: "Operator at the start of a block" })),
             }
         }
         CharType::Whitespace => (),
-        CharType::Bracket => todo!(),
+        CharType::Bracket => open_bracket(character, index, context)?,
         CharType::Point => return Err(Box::from(InvalidCharacter { character, index, message: "Point at the start of a block" })),
+        CharType::Quote => context.state = BufferState::Text,
         CharType::Unknown => return Err(Box::from(InvalidCharacter { character, index, message: "Unknown symbol" })),
     };
     Ok(())
 }
 
-fn parse_number(character: char, char_type: CharType, index: usize, context: &mut ParserContext) -> Result<(), Box<dyn Error>> {
+fn parse_number<N: Number>(character: char, char_type: CharType, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
     match char_type {
         CharType::Number => context.buffer.push(character),
+        // an `i` suffix makes the literal imaginary, for types that have an imaginary unit
+        CharType::Letter if character == 'i' && N::parse_constant("i").is_some() => context.buffer.push(character),
+        CharType::Letter if character == 'i' => return Err(Box::from(InvalidCharacter { character, index, message: "Imaginary literals need complex mode" })),
         CharType::Letter => return Err(Box::from(InvalidCharacter { character, index, message: "Letter inside number" })), //todo implicit multiplication
         CharType::Operator => {
             parse_buffer(context)?;
@@ -99,14 +211,22 @@ fn parse_number(character: char, char_type: CharType, index: usize, context: &mu
             parse_buffer(context)?;
             context.state = BufferState::Empty;
         },
-        CharType::Bracket => todo!(),
+        CharType::Bracket => {
+            parse_buffer(context)?;
+            open_bracket(character, index, context)?
+        }
+        CharType::Point if !N::FRACTIONAL => return Err(Box::from(InvalidCharacter { character, index, message: "Fractional literal in integer mode" })),
         CharType::Point => context.buffer.push(character),
+        CharType::Quote => {
+            parse_buffer(context)?;
+            parse_empty(character, char_type, index, context)?
+        }
         CharType::Unknown => return Err(Box::from(InvalidCharacter { character, index, message: "Unknown symbol" })),
     }
     Ok(())
 }
 
-fn parse_name(character: char, char_type: CharType, index: usize, context: &mut ParserContext) -> Result<(), Box<dyn Error>> {
+fn parse_name<N: Number>(character: char, char_type: CharType, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
     match char_type {
         CharType::Number => context.buffer.push(character),
         CharType::Letter => context.buffer.push(character),
@@ -119,31 +239,308 @@ fn parse_name(character: char, char_type: CharType, index: usize, context: &mut
             parse_buffer(context)?;
             context.state = BufferState::Empty;
         },
-        CharType::Bracket => todo!(),
+        CharType::Bracket if character == '(' || character == '[' => {
+            context.function = Some(context.buffer.clone());
+            context.bracket = character;
+            context.contents = index + 1;
+            context.buffer = String::new();
+            context.state = BufferState::Bracket;
+        }
+        CharType::Bracket => {
+            parse_buffer(context)?;
+            open_bracket(character, index, context)?
+        }
         CharType::Point => context.buffer.push(character),
+        CharType::Quote => {
+            parse_buffer(context)?;
+            parse_empty(character, char_type, index, context)?
+        }
         CharType::Unknown => return Err(Box::from(InvalidCharacter { character, index, message: "Unknown symbol" })),
     }
     Ok(())
 }
 
+/// Splits the contents of a call's brackets at the commas outside nested brackets and strings.
+/// Each argument comes with the position of its first character in `buffer`.
+fn split_arguments(buffer: &str) -> Vec<(usize, &str)> {
+    let mut arguments = Vec::new();
+    let mut depth = 0;
+    let (mut start, mut start_position) = (0, 0);
+    let mut quotes = Quotes::default();
+    for (position, (index, character)) in buffer.char_indices().enumerate() {
+        if !quotes.outside(character) {
+            continue;
+        }
+        match character {
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            ',' if depth == 0 => {
+                arguments.push((start_position, &buffer[start..index]));
+                (start, start_position) = (index + 1, position + 1);
+            }
+            _ => (),
+        }
+    }
+    arguments.push((start_position, &buffer[start..]));
+    arguments
+}
+
+/// Parses the arguments of a call whose brackets' contents start at `offset` in the whole input.
+fn parse_arguments<N: Number>(buffer: &str, offset: usize) -> Result<Arguments<N>, Box<dyn Error>> {
+    if buffer.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    split_arguments(buffer).into_iter().map(|(position, argument)| parse_at::<N>(argument, offset + position)).collect()
+}
+
+fn parse_conditional<N: Number>(buffer: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let arguments = split_arguments(buffer);
+    if arguments.len() != 3 {
+        return Err(Box::from(ParsingError { message: "Conditional needs a condition and two values" }));
+    }
+    let argument = |index: usize| parse_at::<N>(arguments[index].1, offset + arguments[index].0);
+    Ok(Box::from(Conditional { condition: argument(0)?, value: argument(1)?, otherwise: argument(2)? }))
+}
+
+/// Arguments alternate between conditions and values, and an odd one at the end is the default.
+fn parse_piecewise<N: Number>(buffer: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let mut arguments = parse_arguments::<N>(buffer, offset)?;
+    if arguments.len() < 2 {
+        return Err(Box::from(ParsingError { message: "Piecewise needs at least one condition and value" }));
+    }
+    let default = if arguments.len() % 2 == 1 { arguments.pop() } else { None };
+    let mut arguments = arguments.into_iter();
+    let mut arms = Vec::new();
+    while let (Some(condition), Some(value)) = (arguments.next(), arguments.next()) {
+        arms.push((condition, value));
+    }
+    Ok(Box::from(Piecewise { arms, default }))
+}
+
+/// Sums and products take the index variable, the lower and upper bound, and the body.
+fn parse_series<N: Number>(name: &str, buffer: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let arguments = split_arguments(buffer);
+    if arguments.len() != 4 {
+        return Err(Box::from(ParsingError { message: "Sum and product need an index variable, two bounds and a body" }));
+    }
+    let variable = arguments[0].1.trim().to_string();
+    if !is_name(&variable) {
+        return Err(Box::from(ParsingError { message: "Invalid index variable" }));
+    }
+    let argument = |index: usize| parse_at::<N>(arguments[index].1, offset + arguments[index].0);
+    let (lower, upper, body) = (argument(1)?, argument(2)?, argument(3)?);
+    match name {
+        "sum" | "Σ" => Ok(Box::from(Summation { variable, lower, upper, body })),
+        _ => Ok(Box::from(Product { variable, lower, upper, body })),
+    }
+}
+
+fn parse_matrix_operation<N: Number>(name: &str, buffer: &str, offset: usize, span: Option<Span>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let mut arguments = parse_arguments::<N>(buffer, offset)?;
+    if arguments.len() != 1 {
+        return Err(Box::from(ArgumentCount { name: name.to_string(), expected: 1, actual: arguments.len() }));
+    }
+    Ok(Box::from(MatrixOperation { function: MatrixFunction::parse_matrix_function(name).unwrap(), argument: arguments.remove(0), span }))
+}
+
+/// `upper` and `lower` take one argument, and `concat` any number of them.
+fn parse_text_operation<N: Number>(name: &str, buffer: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let function = TextFunction::parse_text_function(name).unwrap();
+    let arguments = parse_arguments::<N>(buffer, offset)?;
+    if function != TextFunction::Concat && arguments.len() != 1 {
+        return Err(Box::from(ArgumentCount { name: name.to_string(), expected: 1, actual: arguments.len() }));
+    }
+    Ok(Box::from(TextOperation { function, arguments }))
+}
+
+pub(crate) fn is_name(name: &str) -> bool {
+    name.chars().next().is_some_and(char::is_alphabetic) && name.chars().all(char::is_alphanumeric)
+}
+
+fn is_series(name: &str) -> bool {
+    matches!(name, "Σ" | "prod" | "Π")
+}
+
+/// `sum` with four arguments, the first a bare name, sums over a range; any other `sum` is the aggregate.
+fn is_range_sum(buffer: &str) -> bool {
+    let arguments = split_arguments(buffer);
+    arguments.len() == 4 && is_name(arguments[0].1.trim())
+}
+
+/// Names the parser reads as something other than a function call or variable.
+pub(crate) fn is_reserved(name: &str) -> bool {
+    matches!(name, "if" | "piecewise" | "not") || is_series(name)
+}
+
+/// Position of the first `keyword` standing as a whole word outside brackets and strings.
+fn find_keyword(text: &str, keyword: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut previous = ' ';
+    let mut quotes = Quotes::default();
+    for (index, character) in text.char_indices() {
+        let outside = quotes.outside(character);
+        match character {
+            _ if !outside => (),
+            '(' | '[' => depth += 1,
+            ')' | ']' => depth -= 1,
+            _ if depth == 0 && !previous.is_alphanumeric() && text[index..].starts_with(keyword)
+                && !text[index + keyword.len()..].starts_with(char::is_alphanumeric) => return Some(index),
+            _ => (),
+        }
+        previous = character;
+    }
+    None
+}
+
+/// Text after the `let` keyword, if the expression is a binding. Bindings are only recognised
+/// at the start of an expression, so the body extends to its end.
+fn strip_let(input: &str) -> Option<&str> {
+    input.trim_start().strip_prefix("let").filter(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// `name = value in body`, following the `let` keyword at `offset` in the whole input.
+fn parse_let<N: Number>(text: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    let (name, rest) = text.split_once('=').ok_or(ParsingError { message: "Let binding without '='" })?;
+    let name = name.trim();
+    if !is_name(name) {
+        return Err(Box::from(ParsingError { message: "Invalid name in let binding" }));
+    }
+    let body_start = find_keyword(rest, "in").ok_or(ParsingError { message: "Let binding without 'in'" })?;
+    let (value, body) = (&rest[..body_start], &rest[body_start + 2..]);
+    Ok(Box::from(Let {
+        name: name.to_string(),
+        value: parse_at::<N>(value, suffix_offset(text, rest, offset))?,
+        body: parse_at::<N>(body, suffix_offset(text, body, offset))?,
+    }))
+}
+
+/// Position of `suffix`, the end of `text`, given that `text` starts at `offset`.
+fn suffix_offset(text: &str, suffix: &str, offset: usize) -> usize {
+    offset + text.chars().count() - suffix.chars().count()
+}
+
+/// Operators are kept in the buffer until it is clear whether a `=` follows, as in `<=`.
+fn parse_operator<N: Number>(character: char, char_type: CharType, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    match character {
+        '=' if "<>=!".contains(context.buffer.as_str()) => {
+            context.buffer.push(character);
+            parse_buffer(context)
+        }
+        _ => {
+            parse_buffer(context)?;
+            parse_empty(character, char_type, index, context)
+        }
+    }
+}
+
+fn open_bracket<N: Number>(character: char, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    match character {
+        '[' if context.follows_complete_expression() => return Err(Box::from(InvalidCharacter { character, index, message: "Only named lists can be indexed" })),
+        '(' | '[' => {
+            context.state = BufferState::Bracket;
+            context.bracket = character;
+            context.start = index;
+            context.contents = index + 1;
+        }
+        ')' | ']' => return Err(Box::from(InvalidCharacter { character, index, message: "Unmatched closing bracket" })),
+        _ => return Err(Box::from(InvalidCharacter { character, index, message: "Unsupported bracket" })),
+    }
+    Ok(())
+}
+
+/// Collects the bracket contents, which are parsed as a separate expression once the bracket closes.
+fn parse_bracket<N: Number>(character: char, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    match character {
+        _ if !context.quotes.outside(character) => context.buffer.push(character),
+        ')' | ']' if context.depth == 0 => match (context.bracket, character) {
+            ('(', ')') | ('[', ']') => parse_buffer(context)?,
+            _ => return Err(Box::from(InvalidCharacter { character, index, message: "Mismatched closing bracket" })),
+        },
+        ')' | ']' => {
+            context.depth -= 1;
+            context.buffer.push(character)
+        }
+        '(' | '[' => {
+            context.depth += 1;
+            context.buffer.push(character)
+        }
+        _ => context.buffer.push(character),
+    }
+    Ok(())
+}
+
+/// Collects the characters of a string literal until the closing quote.
+fn parse_text<N: Number>(character: char, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    match character {
+        '"' => parse_buffer(context)?,
+        '\\' => context.state = BufferState::Escape,
+        _ => context.buffer.push(character),
+    }
+    Ok(())
+}
+
+fn parse_escape<N: Number>(character: char, index: usize, context: &mut ParserContext<N>) -> Result<(), Box<dyn Error>> {
+    let escaped = match character {
+        '"' | '\\' => character,
+        'n' => '\n',
+        't' => '\t',
+        _ => return Err(Box::from(InvalidCharacter { character, index, message: "Unknown escape sequence" })),
+    };
+    context.buffer.push(escaped);
+    context.state = BufferState::Text;
+    Ok(())
+}
+
 pub fn parse_string(string_to_parse: String) -> Result<Box<dyn Expression>, Box<dyn Error>> {
+    parse_string_as::<f64>(string_to_parse)
+}
+
+/// Parses the string into an expression tree over the chosen numeric type.
+pub fn parse_string_as<N: Number>(string_to_parse: String) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    parse_at(&string_to_parse, 0)
+}
+
+/// Parses text that starts at `offset` in the whole input, so that positions in errors and spans
+/// count from the start of the input.
+pub(crate) fn parse_at<N: Number>(string_to_parse: &str, offset: usize) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+    if let Some(binding) = strip_let(string_to_parse) {
+        return parse_let(binding, suffix_offset(string_to_parse, binding, offset));
+    }
     let mut context = ParserContext {
         buffer: String::new(),
         state: BufferState::Empty,
         expression: None,
+        function: None,
+        bracket: '(',
+        depth: 0,
+        quotes: Quotes::default(),
+        start: offset,
+        contents: offset,
+        index: offset,
     };
 
     for (index, character) in string_to_parse.chars().enumerate() {
+        let index = offset + index;
         let char_type = CharType::parse_char_type(character);
+        context.index = index;
 
         match context.state {
             BufferState::Empty => parse_empty(character, char_type, index, &mut context)?,
             BufferState::Number => parse_number(character, char_type, index, &mut context)?,
             BufferState::Name => parse_name(character, char_type, index, &mut context)?,
-            BufferState::Bracket => todo!(),
+            BufferState::Operator => parse_operator(character, char_type, index, &mut context)?,
+            BufferState::Bracket => parse_bracket(character, index, &mut context)?,
+            BufferState::Text => parse_text(character, &mut context)?,
+            BufferState::Escape => parse_escape(character, index, &mut context)?,
         };
     }
 
+    if context.state == BufferState::Bracket {
+        return Err(Box::from(ParsingError { message: "Unclosed bracket" }));
+    }
+    if matches!(context.state, BufferState::Text | BufferState::Escape) {
+        return Err(Box::from(ParsingError { message: "Unclosed string" }));
+    }
     if !context.buffer.is_empty() {
         parse_buffer(&mut context)?;
     }
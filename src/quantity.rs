@@ -0,0 +1,253 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::enums::BuiltinFunction;
+use crate::errors::{ArithmeticError, DimensionMismatch};
+use crate::expression::{ExpressionArgs, ExpressionSettings};
+use crate::number::Number;
+use crate::parser::parse_string_as;
+
+const BASE_UNITS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+/// Exponents of the SI base units, in the order of `BASE_UNITS`.
+type Dimension = [i32; 7];
+
+const DIMENSIONLESS: Dimension = [0; 7];
+
+/// Known unit names, with their size in SI base units and their dimension.
+const UNITS: [(&str, f64, Dimension); 21] = [
+    ("m", 1.0, [1, 0, 0, 0, 0, 0, 0]),
+    ("km", 1000.0, [1, 0, 0, 0, 0, 0, 0]),
+    ("cm", 0.01, [1, 0, 0, 0, 0, 0, 0]),
+    ("mm", 0.001, [1, 0, 0, 0, 0, 0, 0]),
+    ("kg", 1.0, [0, 1, 0, 0, 0, 0, 0]),
+    ("g", 0.001, [0, 1, 0, 0, 0, 0, 0]),
+    ("s", 1.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("ms", 0.001, [0, 0, 1, 0, 0, 0, 0]),
+    ("min", 60.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("h", 3600.0, [0, 0, 1, 0, 0, 0, 0]),
+    ("A", 1.0, [0, 0, 0, 1, 0, 0, 0]),
+    ("K", 1.0, [0, 0, 0, 0, 1, 0, 0]),
+    ("mol", 1.0, [0, 0, 0, 0, 0, 1, 0]),
+    ("cd", 1.0, [0, 0, 0, 0, 0, 0, 1]),
+    ("N", 1.0, [1, 1, -2, 0, 0, 0, 0]),
+    ("J", 1.0, [2, 1, -2, 0, 0, 0, 0]),
+    ("W", 1.0, [2, 1, -3, 0, 0, 0, 0]),
+    ("Pa", 1.0, [-1, 1, -2, 0, 0, 0, 0]),
+    ("Hz", 1.0, [0, 0, -1, 0, 0, 0, 0]),
+    ("C", 1.0, [0, 0, 1, 1, 0, 0, 0]),
+    ("V", 1.0, [2, 1, -3, -1, 0, 0, 0]),
+];
+
+/// Unit a quantity is displayed in: named units with exponents, and the size of the whole unit in SI.
+#[derive(Clone, Debug, PartialEq)]
+struct Unit {
+    factors: Vec<(String, i32)>,
+    scale: f64,
+}
+
+/// Physical quantity: a value in SI base units, its dimension, and the unit it is written in.
+///
+/// Unit names such as `m`, `km` or `h` are constants, so `5 m / 2 s` is a speed. Products and
+/// quotients derive their unit from their operands, and `x to km/h` changes the unit a value
+/// is written in. Adding or subtracting quantities of different dimensions is an error.
+#[derive(Clone, Debug)]
+pub struct Quantity {
+    value: f64,
+    dimension: Dimension,
+    unit: Option<Unit>,
+}
+
+fn format_factors<S: AsRef<str>>(factors: &[(S, i32)]) -> String {
+    let format_factor = |name: &S, exponent: i32| match exponent {
+        1 => name.as_ref().to_string(),
+        _ => format!("{}^{}", name.as_ref(), exponent),
+    };
+    let numerator: Vec<String> = factors.iter().filter(|(_, exponent)| *exponent > 0).map(|(name, exponent)| format_factor(name, *exponent)).collect();
+    let denominator: Vec<String> = factors.iter().filter(|(_, exponent)| *exponent < 0).map(|(name, exponent)| format_factor(name, -exponent)).collect();
+    let numerator = if numerator.is_empty() { String::from("1") } else { numerator.join("*") };
+    match denominator.len() {
+        0 => numerator,
+        1 => format!("{}/{}", numerator, denominator[0]),
+        _ => format!("{}/({})", numerator, denominator.join("*")),
+    }
+}
+
+fn format_dimension(dimension: &Dimension) -> String {
+    let factors: Vec<(&str, i32)> = BASE_UNITS.iter().zip(dimension).map(|(name, exponent)| (*name, *exponent)).collect();
+    format_factors(&factors)
+}
+
+fn mismatch(left: &Dimension, right: &Dimension) -> Box<dyn Error> {
+    Box::from(DimensionMismatch { left: format_dimension(left), right: format_dimension(right), expression: None })
+}
+
+impl Unit {
+    /// Product of `self` and `other` raised to `exponent`, which is 1 or -1.
+    fn combine(&self, other: &Unit, exponent: i32) -> Option<Unit> {
+        let mut factors = self.factors.clone();
+        for (name, other_exponent) in &other.factors {
+            match factors.iter_mut().find(|(factor, _)| factor == name) {
+                Some((_, own_exponent)) => *own_exponent += other_exponent * exponent,
+                None => factors.push((name.clone(), other_exponent * exponent)),
+            }
+        }
+        factors.retain(|(_, exponent)| *exponent != 0);
+        match factors.is_empty() {
+            true => None,
+            false => Some(Unit { factors, scale: self.scale * other.scale.powi(exponent) }),
+        }
+    }
+
+    fn power(&self, exponent: i32) -> Unit {
+        Unit { factors: self.factors.iter().map(|(name, own_exponent)| (name.clone(), own_exponent * exponent)).collect(), scale: self.scale.powi(exponent) }
+    }
+}
+
+impl Quantity {
+    /// Creates a quantity from a value and a unit expression such as `km/h` or `kg*m/s^2`.
+    pub fn new(value: f64, unit: &str) -> Result<Quantity, Box<dyn Error>> {
+        let unit = parse_string_as::<Quantity>(unit.to_string())?.evaluate(&ExpressionArgs::empty())?;
+        Quantity::dimensionless(value).multiply(&unit, &ExpressionSettings::default())
+    }
+
+    pub fn dimensionless(value: f64) -> Quantity {
+        Quantity { value, dimension: DIMENSIONLESS, unit: None }
+    }
+
+    /// Value in SI base units.
+    pub fn si_value(&self) -> f64 { self.value }
+
+    /// Value in the unit the quantity is displayed in.
+    pub fn value(&self) -> f64 {
+        match &self.unit {
+            None => self.value,
+            Some(unit) => self.value / unit.scale,
+        }
+    }
+
+    /// The unit the quantity is displayed in, empty for dimensionless values.
+    pub fn unit(&self) -> String {
+        match &self.unit {
+            Some(unit) => format_factors(&unit.factors),
+            None if self.is_dimensionless() => String::new(),
+            None => format_dimension(&self.dimension),
+        }
+    }
+
+    pub fn is_dimensionless(&self) -> bool { self.dimension == DIMENSIONLESS }
+
+    fn require_dimension(&self, dimension: &Dimension) -> Result<(), Box<dyn Error>> {
+        match self.dimension == *dimension {
+            true => Ok(()),
+            false => Err(mismatch(&self.dimension, dimension)),
+        }
+    }
+
+    fn with_dimension_of(&self, value: f64, other: &Quantity) -> Result<Quantity, Box<dyn Error>> {
+        self.require_dimension(&other.dimension)?;
+        let unit = if self.unit == other.unit { self.unit.clone() } else { None };
+        Ok(Quantity { value, dimension: self.dimension, unit })
+    }
+
+    /// Unit of a product (`exponent` 1) or quotient (`exponent` -1). A dimensionless operand
+    /// without a unit leaves the unit of the other one unchanged.
+    fn product_unit(&self, other: &Quantity, exponent: i32) -> Option<Unit> {
+        match (&self.unit, &other.unit) {
+            (Some(own), Some(other)) => own.combine(other, exponent),
+            (Some(own), None) if other.is_dimensionless() => Some(own.clone()),
+            (None, Some(other)) if self.is_dimensionless() => Some(other.power(exponent)),
+            _ => None,
+        }
+    }
+}
+
+impl PartialEq for Quantity {
+    fn eq(&self, other: &Self) -> bool { self.value == other.value && self.dimension == other.dimension }
+}
+
+impl Display for Quantity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.unit().as_str() {
+            "" => write!(f, "{}", self.value()),
+            unit => write!(f, "{} {}", self.value(), unit),
+        }
+    }
+}
+
+impl Number for Quantity {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> { Ok(Quantity::dimensionless(literal.parse::<f64>()?)) }
+
+    fn parse_constant(name: &str) -> Option<Self> {
+        UNITS.iter().find(|(unit, _, _)| *unit == name).map(|(unit, scale, dimension)| Quantity {
+            value: *scale,
+            dimension: *dimension,
+            unit: Some(Unit { factors: vec![(unit.to_string(), 1)], scale: *scale }),
+        })
+    }
+
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        self.with_dimension_of(self.value + other.value, other)
+    }
+
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        self.with_dimension_of(self.value - other.value, other)
+    }
+
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let dimension = std::array::from_fn(|i| self.dimension[i] + other.dimension[i]);
+        Ok(Quantity { value: self.value * other.value, dimension, unit: self.product_unit(other, 1) })
+    }
+
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let dimension = std::array::from_fn(|i| self.dimension[i] - other.dimension[i]);
+        Ok(Quantity { value: self.value / other.value, dimension, unit: self.product_unit(other, -1) })
+    }
+
+    /// The exponent must be dimensionless, and must keep the exponents of the base's units whole.
+    fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        exponent.require_dimension(&DIMENSIONLESS)?;
+        let scaled = self.dimension.map(|own| own as f64 * exponent.value);
+        if scaled.iter().any(|own| own.fract() != 0.0) {
+            return Err(Box::from(ArithmeticError { message: "Power would give a fractional unit" }));
+        }
+        let whole_exponent = exponent.value.fract() == 0.0;
+        Ok(Quantity {
+            value: self.value.powf(exponent.value),
+            dimension: scaled.map(|own| own as i32),
+            unit: self.unit.as_ref().filter(|_| whole_exponent).map(|unit| unit.power(exponent.value as i32)),
+        })
+    }
+
+    fn convert(&self, target: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        self.require_dimension(&target.dimension)?;
+        match &target.unit {
+            None => Err(Box::from(ArithmeticError { message: "Conversion target has no unit" })),
+            Some(unit) => Ok(Quantity { value: self.value, dimension: self.dimension, unit: Some(Unit { factors: unit.factors.clone(), scale: target.value }) }),
+        }
+    }
+
+    fn apply_function(&self, function: BuiltinFunction, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        match function {
+            BuiltinFunction::Abs => Ok(Quantity { value: self.value.abs(), dimension: self.dimension, unit: self.unit.clone() }),
+            BuiltinFunction::Sqrt => self.power(&Quantity::dimensionless(0.5), settings),
+            _ => {
+                self.require_dimension(&DIMENSIONLESS)?;
+                Ok(Quantity::dimensionless(self.value.apply_function(function, settings)?))
+            }
+        }
+    }
+
+    fn compare(&self, other: &Self, settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+        self.require_dimension(&other.dimension)?;
+        self.value.compare(&other.value, settings)
+    }
+
+    fn is_zero(&self, delta: f64) -> bool { self.value.abs() <= delta }
+    fn to_index(&self) -> Option<usize> {
+        match self.is_dimensionless() {
+            true => self.value.to_index(),
+            false => None,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::enums::BuiltinFunction;
+use crate::errors::{ArithmeticError, ParsingError, UnsupportedOperation};
+use crate::expression::ExpressionSettings;
+use crate::number::Number;
+
+const OVERFLOW: ArithmeticError = ArithmeticError { message: "Rational overflow" };
+
+/// Exact fraction, always kept reduced and with a positive denominator.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Hash)]
+pub struct Rational {
+    numerator: i128,
+    denominator: i128,
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a.abs()
+}
+
+/// Orders `a / b` and `c / d`, both denominators being positive, by their continued fractions,
+/// so unlike cross-multiplying it cannot overflow.
+fn compare_fractions(a: i128, b: i128, c: i128, d: i128) -> Ordering {
+    let (left_whole, right_whole) = (a.div_euclid(b), c.div_euclid(d));
+    if left_whole != right_whole {
+        return left_whole.cmp(&right_whole);
+    }
+    match (a.rem_euclid(b), c.rem_euclid(d)) {
+        (0, 0) => Ordering::Equal,
+        (0, _) => Ordering::Less,
+        (_, 0) => Ordering::Greater,
+        // the remainders are below one, so the larger one has the smaller reciprocal
+        (left, right) => compare_fractions(d, right, b, left),
+    }
+}
+
+impl Rational {
+    pub fn new(numerator: i128, denominator: i128) -> Result<Rational, Box<dyn Error>> {
+        if denominator == 0 {
+            return Err(Box::from(ArithmeticError { message: "Division by zero" }));
+        }
+        let divisor = gcd(numerator, denominator);
+        let sign = if denominator < 0 { -1 } else { 1 };
+        Ok(Rational {
+            numerator: (numerator / divisor).checked_mul(sign).ok_or(OVERFLOW)?,
+            denominator: (denominator / divisor).checked_mul(sign).ok_or(OVERFLOW)?,
+        })
+    }
+
+    pub fn from_integer(value: i128) -> Rational {
+        Rational { numerator: value, denominator: 1 }
+    }
+
+    pub fn numerator(&self) -> i128 { self.numerator }
+
+    pub fn denominator(&self) -> i128 { self.denominator }
+
+    pub fn to_f64(&self) -> f64 { self.numerator as f64 / self.denominator as f64 }
+
+    /// Formats the value with exactly `precision` fractional digits, rounding half away from zero.
+    pub fn to_decimal_string(&self, precision: usize) -> String {
+        let whole = self.numerator.unsigned_abs() / self.denominator as u128;
+        let mut remainder = self.numerator.unsigned_abs() % self.denominator as u128;
+        let mut digits: Vec<u8> = whole.to_string().bytes().map(|digit| digit - b'0').collect();
+        for _ in 0..=precision {
+            // remainder < denominator <= i128::MAX, so remainder * 10 always fits in u128
+            remainder *= 10;
+            digits.push((remainder / self.denominator as u128) as u8);
+            remainder %= self.denominator as u128;
+        }
+
+        let round_up = digits.pop().unwrap() >= 5;
+        if round_up {
+            let mut index = digits.len();
+            loop {
+                if index == 0 {
+                    digits.insert(0, 1);
+                    break;
+                }
+                index -= 1;
+                if digits[index] == 9 {
+                    digits[index] = 0;
+                } else {
+                    digits[index] += 1;
+                    break;
+                }
+            }
+        }
+
+        let split = digits.len() - precision;
+        let mut output = String::new();
+        if self.numerator < 0 && digits.iter().any(|digit| *digit != 0) {
+            output.push('-');
+        }
+        output.extend(digits[..split].iter().map(|digit| (digit + b'0') as char));
+        if precision > 0 {
+            output.push('.');
+            output.extend(digits[split..].iter().map(|digit| (digit + b'0') as char));
+        }
+        output
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.denominator {
+            1 => write!(f, "{}", self.numerator),
+            _ => write!(f, "{}/{}", self.numerator, self.denominator),
+        }
+    }
+}
+
+impl Number for Rational {
+    /// Reads decimal literals such as `21.25` exactly, without going through `f64`.
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+        let (negative, digits) = match literal.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, literal),
+        };
+        let (whole, fraction) = digits.split_once('.').unwrap_or((digits, ""));
+        if whole.is_empty() || !whole.chars().chain(fraction.chars()).all(|c| c.is_ascii_digit()) {
+            return Err(Box::from(ParsingError { message: "Invalid rational literal" }));
+        }
+
+        let mut numerator: i128 = 0;
+        let mut denominator: i128 = 1;
+        for digit in whole.chars().chain(fraction.chars()) {
+            numerator = numerator.checked_mul(10).and_then(|n| n.checked_add(digit as i128 - '0' as i128)).ok_or(OVERFLOW)?;
+        }
+        for _ in fraction.chars() {
+            denominator = denominator.checked_mul(10).ok_or(OVERFLOW)?;
+        }
+        Rational::new(if negative { -numerator } else { numerator }, denominator)
+    }
+
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let divisor = gcd(self.denominator, other.denominator);
+        let left = self.numerator.checked_mul(other.denominator / divisor).ok_or(OVERFLOW)?;
+        let right = other.numerator.checked_mul(self.denominator / divisor).ok_or(OVERFLOW)?;
+        let denominator = self.denominator.checked_mul(other.denominator / divisor).ok_or(OVERFLOW)?;
+        Rational::new(left.checked_add(right).ok_or(OVERFLOW)?, denominator)
+    }
+
+    fn subtract(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        self.add(&Rational { numerator: other.numerator.checked_neg().ok_or(OVERFLOW)?, denominator: other.denominator }, settings)
+    }
+
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        // cross-reduce first so intermediate products stay as small as possible
+        let first = gcd(self.numerator, other.denominator).max(1);
+        let second = gcd(other.numerator, self.denominator).max(1);
+        let numerator = (self.numerator / first).checked_mul(other.numerator / second).ok_or(OVERFLOW)?;
+        let denominator = (self.denominator / second).checked_mul(other.denominator / first).ok_or(OVERFLOW)?;
+        Rational::new(numerator, denominator)
+    }
+
+    fn divide(&self, other: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if other.numerator == 0 {
+            return Err(Box::from(ArithmeticError { message: "Division by zero" }));
+        }
+        self.multiply(&Rational::new(other.denominator, other.numerator)?, settings)
+    }
+
+    /// Only integer exponents are supported, as other powers are generally irrational.
+    fn power(&self, exponent: &Self, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if exponent.denominator != 1 {
+            return Err(Box::from(ArithmeticError { message: "Rational exponent must be an integer" }));
+        }
+        let mut base = if exponent.numerator < 0 { Rational::from_integer(1).divide(self, settings)? } else { *self };
+        let mut remaining = exponent.numerator.unsigned_abs();
+        let mut result = Rational::from_integer(1);
+        while remaining > 0 {
+            if remaining & 1 == 1 {
+                result = result.multiply(&base, settings)?;
+            }
+            remaining >>= 1;
+            if remaining > 0 {
+                base = base.multiply(&base, settings)?;
+            }
+        }
+        Ok(result)
+    }
+
+    fn apply_function(&self, function: BuiltinFunction, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        match function {
+            BuiltinFunction::Abs => Ok(Rational { numerator: self.numerator.checked_abs().ok_or(OVERFLOW)?, denominator: self.denominator }),
+            _ => Err(Box::from(UnsupportedOperation { name: function.get_name() })),
+        }
+    }
+
+    fn compare(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Ordering, Box<dyn Error>> {
+        Ok(compare_fractions(self.numerator, self.denominator, other.numerator, other.denominator))
+    }
+
+    fn is_equal(&self, other: &Self, settings: &ExpressionSettings) -> Result<bool, Box<dyn Error>> {
+        Ok(self.compare(other, settings)? == Ordering::Equal)
+    }
+
+    fn is_zero(&self, _delta: f64) -> bool { self.numerator == 0 }
+    fn to_index(&self) -> Option<usize> {
+        match self.denominator {
+            1 => usize::try_from(self.numerator).ok(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::error::Error;
+use crate::enums::{ExpressionType, TextFunction};
+use crate::errors::TypeMismatch;
+use crate::expression::{attach_to_operand, Expression, Scope};
+use crate::number::Number;
+use crate::value::Value;
+
+/// String literal, written in double quotes. A backslash escapes `"`, `\` and starts `\n` and `\t`.
+#[derive(Clone)]
+pub struct StringLiteral {
+    pub value: String,
+}
+
+/// Call of a string function, written `concat(a, b)`, `upper(s)` or `lower(s)`.
+/// `concat` joins strings and numbers, the latter written as they print.
+#[derive(Clone)]
+pub struct TextOperation<N: Number = f64> {
+    pub function: TextFunction,
+    pub arguments: Vec<Box<dyn Expression<N>>>,
+}
+
+/// The text of a string literal as it is written, with quotes and escapes.
+fn quote(text: &str) -> String {
+    let mut quoted = String::from('"');
+    for character in text.chars() {
+        match character {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            _ => quoted.push(character),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+impl<N: Number> Expression<N> for StringLiteral {
+    fn can_evaluate(&self, _args: &Scope<N>) -> bool { true }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, _args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { Ok(Value::String(self.value.clone())) }
+    fn to_string(&self) -> String { quote(&self.value) }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::StringLiteral }
+    fn children(&self) -> Vec<&dyn Expression<N>> { vec![] }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
+
+impl<N: Number> TextOperation<N> {
+    fn apply(&self, args: &Scope<N>) -> Result<String, Box<dyn Error>> {
+        let mut values = self.arguments.iter().map(|argument| argument.evaluate_value(args)).collect::<Result<Vec<_>, _>>()?;
+        match self.function {
+            TextFunction::Concat => values.into_iter().map(|value| match value {
+                Value::String(text) => Ok(text),
+                Value::Number(value) => Ok(value.to_string()),
+                other => Err(Box::from(TypeMismatch { expected: "string", actual: other.type_name() }) as Box<dyn Error>),
+            }).collect(),
+            TextFunction::Upper => Ok(values.remove(0).into_string()?.to_uppercase()),
+            TextFunction::Lower => Ok(values.remove(0).into_string()?.to_lowercase()),
+        }
+    }
+}
+
+impl<N: Number> Expression<N> for TextOperation<N> {
+    fn can_evaluate(&self, args: &Scope<N>) -> bool { self.arguments.iter().all(|argument| argument.can_evaluate(args)) }
+    fn evaluate(&self, args: &Scope<N>) -> Result<N, Box<dyn Error>> { self.evaluate_value(args)?.into_number() }
+    fn evaluate_value(&self, args: &Scope<N>) -> Result<Value<N>, Box<dyn Error>> { Ok(Value::String(self.apply(args)?)) }
+    fn to_string(&self) -> String {
+        let arguments: Vec<String> = self.arguments.iter().map(|argument| argument.to_string()).collect();
+        format!("{}({})", self.function.get_name(), arguments.join(", "))
+    }
+    fn get_exp_type(&self) -> ExpressionType { ExpressionType::TextOperation }
+    fn children(&self) -> Vec<&dyn Expression<N>> { self.arguments.iter().map(|argument| argument.as_ref()).collect() }
+    fn attach_after(&self, exp: &dyn Expression<N>) -> Result<Box<dyn Expression<N>>, Box<dyn Error>> {
+        attach_to_operand(self, exp)
+    }
+}
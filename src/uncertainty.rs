@@ -0,0 +1,104 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use crate::autodiff::{derivative, power_partials};
+use crate::enums::BuiltinFunction;
+use crate::errors::ArithmeticError;
+use crate::expression::ExpressionSettings;
+use crate::number::Number;
+
+static NEXT_SOURCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Value with a standard uncertainty, propagated to first order.
+///
+/// Every `±` creates an independent source of uncertainty, and the contribution of each source
+/// is tracked separately, so correlated terms cancel: `x - x` is exactly `0 ± 0`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Measurement {
+    value: f64,
+    /// Contribution of each source of uncertainty, by source id.
+    contributions: BTreeMap<usize, f64>,
+}
+
+impl Measurement {
+    pub fn exact(value: f64) -> Measurement {
+        Measurement { value, contributions: BTreeMap::new() }
+    }
+
+    /// Creates a measurement whose uncertainty is independent of every other one.
+    pub fn new(value: f64, uncertainty: f64) -> Result<Measurement, Box<dyn Error>> {
+        if uncertainty.is_nan() || uncertainty < 0.0 {
+            return Err(Box::from(ArithmeticError { message: "Uncertainty must not be negative" }));
+        }
+        let source = NEXT_SOURCE.fetch_add(1, Ordering::Relaxed);
+        Ok(Measurement { value, contributions: BTreeMap::from([(source, uncertainty)]) })
+    }
+
+    pub fn value(&self) -> f64 { self.value }
+
+    /// Standard uncertainty, combining the contributions of all sources in quadrature.
+    pub fn uncertainty(&self) -> f64 {
+        self.contributions.values().fold(0.0, |sum, contribution| sum + contribution * contribution).sqrt()
+    }
+
+    fn combine(&self, self_partial: f64, other: &Measurement, other_partial: f64, value: f64) -> Measurement {
+        let mut contributions = self.chain(self_partial, value).contributions;
+        for (source, contribution) in &other.contributions {
+            *contributions.entry(*source).or_insert(0.0) += contribution * other_partial;
+        }
+        Measurement { value, contributions }
+    }
+
+    fn chain(&self, partial: f64, value: f64) -> Measurement {
+        Measurement { value, contributions: self.contributions.iter().map(|(source, contribution)| (*source, contribution * partial)).collect() }
+    }
+}
+
+impl Display for Measurement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.contributions.is_empty() {
+            true => write!(f, "{}", self.value),
+            false => write!(f, "{} ± {}", self.value, self.uncertainty()),
+        }
+    }
+}
+
+impl Number for Measurement {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> { Ok(Measurement::exact(literal.parse::<f64>()?)) }
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(1.0, other, 1.0, self.value + other.value))
+    }
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(1.0, other, -1.0, self.value - other.value))
+    }
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(other.value, other, self.value, self.value * other.value))
+    }
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value / other.value;
+        Ok(self.combine(1.0 / other.value, other, -value / other.value, value))
+    }
+    fn power(&self, exponent: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        let value = self.value.powf(exponent.value);
+        let (base_partial, exponent_partial) = power_partials(self.value, exponent.value, value, !exponent.contributions.is_empty());
+        Ok(self.combine(base_partial, exponent, exponent_partial, value))
+    }
+    /// Adds an independent source of uncertainty; only the value of the uncertainty operand is used.
+    fn with_uncertainty(&self, uncertainty: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.combine(1.0, &Measurement::new(0.0, uncertainty.value)?, 1.0, self.value))
+    }
+    fn apply_function(&self, function: BuiltinFunction, settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        Ok(self.chain(derivative(function, self.value), self.value.apply_function(function, settings)?))
+    }
+    /// Compares the values only, ignoring the uncertainty.
+    fn compare(&self, other: &Self, settings: &ExpressionSettings) -> Result<std::cmp::Ordering, Box<dyn Error>> { self.value.compare(&other.value, settings) }
+    fn is_zero(&self, delta: f64) -> bool { self.value.abs() <= delta }
+    /// Only a value without uncertainty is a list position.
+    fn to_index(&self) -> Option<usize> {
+        match self.contributions.values().all(|contribution| *contribution == 0.0) {
+            true => self.value.to_index(),
+            false => None,
+        }
+    }
+}
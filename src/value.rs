@@ -0,0 +1,131 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use crate::errors::{ShapeMismatch, TypeMismatch};
+use crate::expression::ExpressionSettings;
+use crate::matrix::Matrix;
+use crate::number::Number;
+
+/// Result of evaluating an expression that is not necessarily a number, such as a comparison.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value<N: Number = f64> {
+    Number(N),
+    Boolean(bool),
+    List(Vec<N>),
+    Matrix(Matrix<N>),
+    String(String),
+}
+
+impl<N: Number> Value<N> {
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Value::Number(_) => "number",
+            Value::Boolean(_) => "boolean",
+            Value::List(_) => "list",
+            Value::Matrix(_) => "matrix",
+            Value::String(_) => "string",
+        }
+    }
+
+    pub fn into_number(self) -> Result<N, Box<dyn Error>> {
+        match self {
+            Value::Number(value) => Ok(value),
+            other => Err(Box::from(TypeMismatch { expected: "number", actual: other.type_name() })),
+        }
+    }
+
+    pub fn into_boolean(self) -> Result<bool, Box<dyn Error>> {
+        match self {
+            Value::Boolean(value) => Ok(value),
+            other => Err(Box::from(TypeMismatch { expected: "boolean", actual: other.type_name() })),
+        }
+    }
+
+    pub fn into_list(self) -> Result<Vec<N>, Box<dyn Error>> {
+        match self {
+            Value::List(values) => Ok(values),
+            other => Err(Box::from(TypeMismatch { expected: "list", actual: other.type_name() })),
+        }
+    }
+
+    pub fn into_matrix(self) -> Result<Matrix<N>, Box<dyn Error>> {
+        match self {
+            Value::Matrix(matrix) => Ok(matrix),
+            other => Err(Box::from(TypeMismatch { expected: "matrix", actual: other.type_name() })),
+        }
+    }
+
+    pub fn into_string(self) -> Result<String, Box<dyn Error>> {
+        match self {
+            Value::String(text) => Ok(text),
+            other => Err(Box::from(TypeMismatch { expected: "string", actual: other.type_name() })),
+        }
+    }
+
+    fn shape(&self) -> String {
+        match self {
+            Value::List(values) => format!("list of {}", values.len()),
+            Value::Matrix(matrix) => matrix.shape(),
+            other => other.type_name().to_string(),
+        }
+    }
+
+    /// Applies `operation` to a number, or to every element of a list or matrix.
+    pub(crate) fn map(&self, operation: impl Fn(&N) -> Result<N, Box<dyn Error>>) -> Result<Value<N>, Box<dyn Error>> {
+        match self {
+            Value::Number(value) => Ok(Value::Number(operation(value)?)),
+            Value::List(values) => Ok(Value::List(values.iter().map(operation).collect::<Result<_, _>>()?)),
+            Value::Matrix(matrix) => Ok(Value::Matrix(matrix.map(operation)?)),
+            other => Err(Box::from(TypeMismatch { expected: "number", actual: other.type_name() })),
+        }
+    }
+
+    /// Applies `operation` element by element. A number is combined with every element of a
+    /// list or matrix, and two lists or matrices must have the same shape.
+    pub(crate) fn broadcast(&self, other: &Value<N>, operation: impl Fn(&N, &N) -> Result<N, Box<dyn Error>>) -> Result<Value<N>, Box<dyn Error>> {
+        match (self, other) {
+            (Value::Number(left), right) => right.map(|right| operation(left, right)),
+            (left, Value::Number(right)) => left.map(|left| operation(left, right)),
+            (Value::List(left), Value::List(right)) if left.len() == right.len() => {
+                Ok(Value::List(left.iter().zip(right).map(|(left, right)| operation(left, right)).collect::<Result<_, _>>()?))
+            }
+            (Value::Matrix(left), Value::Matrix(right)) => Ok(Value::Matrix(left.zip_with(right, operation)?)),
+            (Value::List(_) | Value::Matrix(_), Value::List(_) | Value::Matrix(_)) => {
+                Err(Box::from(ShapeMismatch { left: self.shape(), right: other.shape(), expression: None, span: None }))
+            }
+            (other @ (Value::Boolean(_) | Value::String(_)), _) | (_, other) => Err(Box::from(TypeMismatch { expected: "number", actual: other.type_name() })),
+        }
+    }
+
+    /// Product for `*`: the matrix product when either side is a matrix, with a list as a
+    /// column vector on the right and a row vector on the left, and `broadcast` otherwise.
+    pub(crate) fn product(&self, other: &Value<N>, settings: &ExpressionSettings) -> Result<Value<N>, Box<dyn Error>> {
+        match (self, other) {
+            (Value::Matrix(left), Value::Matrix(right)) => Ok(Value::Matrix(left.multiply(right, settings)?)),
+            (Value::Matrix(left), Value::List(right)) if left.columns() == right.len() => {
+                Ok(Value::List(left.multiply(&Matrix::new(right.len(), 1, right.clone())?, settings)?.values().to_vec()))
+            }
+            (Value::List(left), Value::Matrix(right)) if left.len() == right.rows() => {
+                Ok(Value::List(Matrix::new(1, left.len(), left.clone())?.multiply(right, settings)?.values().to_vec()))
+            }
+            (Value::Matrix(_), Value::List(_)) | (Value::List(_), Value::Matrix(_)) => {
+                Err(Box::from(ShapeMismatch { left: self.shape(), right: other.shape(), expression: None, span: None }))
+            }
+            _ => self.broadcast(other, |left, right| left.multiply(right, settings)),
+        }
+    }
+}
+
+impl<N: Number> Display for Value<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Value::Number(value) => write!(f, "{}", value),
+            Value::Boolean(value) => write!(f, "{}", value),
+            Value::List(values) => {
+                let values: Vec<String> = values.iter().map(|value| value.to_string()).collect();
+                write!(f, "[{}]", values.join(", "))
+            }
+            Value::Matrix(matrix) => write!(f, "{}", matrix),
+            Value::String(text) => write!(f, "{}", text),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use std::sync::Arc;
+use expression_parser::autodiff::{forward_gradient, reverse_gradient, Dual, Tracked};
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string_as;
+
+fn variable_args(variables: &[(&str, f64)]) -> ExpressionArgs {
+    let mut args = ExpressionArgs::empty();
+    for (name, value) in variables {
+        args.variables.insert(name.to_string(), *value);
+    }
+    args
+}
+
+fn both_gradients(input: &str, args: &ExpressionArgs, variables: &[&str]) -> [(f64, Vec<f64>); 2] {
+    let forward = parse_string_as::<Dual>(input.to_string()).unwrap();
+    let reverse = parse_string_as::<Tracked>(input.to_string()).unwrap();
+    [forward_gradient(forward.as_ref(), args, variables).unwrap(), reverse_gradient(reverse.as_ref(), args, variables).unwrap()]
+}
+
+fn assert_close(actual: &[f64], expected: &[f64]) {
+    assert_eq!(actual.len(), expected.len());
+    for (actual, expected) in actual.iter().zip(expected) {
+        assert!((actual - expected).abs() < 1e-10, "expected {:?}, got {:?}", expected, actual);
+    }
+}
+
+#[test]
+fn test_gradient_polynomial() {
+    let args = variable_args(&[("x", 3.0), ("y", 2.0)]);
+    for (value, gradient) in both_gradients("x * x * y + 2 * y - x / y", &args, &["x", "y"]) {
+        assert_eq!(value, 20.5);
+        assert_close(&gradient, &[11.5, 11.75]);
+    }
+}
+
+#[test]
+fn test_gradient_subset_of_variables() {
+    let args = variable_args(&[("x", 3.0), ("y", 2.0)]);
+    for (value, gradient) in both_gradients("x * y + 1", &args, &["y"]) {
+        assert_eq!(value, 7.0);
+        assert_close(&gradient, &[3.0]);
+    }
+    for (_, gradient) in both_gradients("5", &args, &["x", "y"]) {
+        assert_close(&gradient, &[0.0, 0.0]);
+    }
+}
+
+#[test]
+fn test_gradient_power() {
+    let args = variable_args(&[("x", 2.0), ("y", 3.0)]);
+    for (value, gradient) in both_gradients("x ^ y", &args, &["x", "y"]) {
+        assert_eq!(value, 8.0);
+        assert_close(&gradient, &[12.0, 8.0 * 2f64.ln()]);
+    }
+    let negative = variable_args(&[("x", -2.0)]);
+    for (_, gradient) in both_gradients("x ^ 3", &negative, &["x"]) {
+        assert_close(&gradient, &[12.0]);
+    }
+}
+
+#[test]
+fn test_gradient_builtin_functions() {
+    let x = 0.7;
+    let args = variable_args(&[("x", x)]);
+    let cases: [(&str, f64); 7] = [
+        ("sqrt(x)", 0.5 / x.sqrt()),
+        ("exp(x)", x.exp()),
+        ("ln(x)", 1.0 / x),
+        ("sin(x)", x.cos()),
+        ("cos(x)", -x.sin()),
+        ("tan(x)", 1.0 / (x.cos() * x.cos())),
+        ("abs(x - 1)", -1.0),
+    ];
+    for (input, expected) in cases {
+        for (_, gradient) in both_gradients(input, &args, &["x"]) {
+            assert_close(&gradient, &[expected]);
+        }
+    }
+    for (_, gradient) in both_gradients("sin(x * x)", &args, &["x"]) {
+        assert_close(&gradient, &[2.0 * x * (x * x).cos()]);
+    }
+}
+
+#[test]
+fn test_gradient_unknown_variable() {
+    let exp = parse_string_as::<Dual>("x + 1".to_string()).unwrap();
+    let result = forward_gradient(exp.as_ref(), &ExpressionArgs::empty(), &["x"]);
+    assert_eq!(result.err().unwrap().to_string(), "Unknown variable 'x'");
+}
+
+#[test]
+fn test_piecewise_gradient() {
+    let input = "piecewise(x < 1, x ^ 2, 3 * x)";
+    for gradient in both_gradients(input, &variable_args(&[("x", 0.5)]), &["x"]) {
+        assert_eq!(gradient, (0.25, vec![1.0]));
+    }
+    for gradient in both_gradients(input, &variable_args(&[("x", 2.0)]), &["x"]) {
+        assert_eq!(gradient, (6.0, vec![3.0]));
+    }
+}
+
+#[test]
+fn test_gradient_keeps_context() {
+    let mut args = variable_args(&[("x", 2.0), ("y", 3.0)]);
+    args.definitions.define("f(t) = t ^ 2 + 1").unwrap();
+    args.functions.insert("cube".to_string(), Arc::new(|value| value * value * value));
+    args.lists.insert("ws".to_string(), vec![2.0, 3.0]);
+    args.strings.insert("label".to_string(), "abc".to_string());
+    for (value, gradient) in both_gradients("f(x) * ws[1] + cube(y) + len(label)", &args, &["x", "y"]) {
+        assert_eq!(value, 45.0);
+        assert_eq!(gradient[0], 12.0);
+        assert!((gradient[1] - 27.0).abs() < 1e-6, "expected 27, got {}", gradient[1]);
+    }
+}
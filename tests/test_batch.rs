@@ -0,0 +1,131 @@
+use expression_parser::batch::{evaluate_batch, evaluate_batch_parallel, BatchArgs, BATCH_SIZE};
+use expression_parser::enums::NonFinitePolicy;
+use expression_parser::errors::ArithmeticError;
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+
+#[test]
+fn test_batch_constant() {
+    let exp = parse_string("5 / 2".to_string()).unwrap();
+    let mut output = [0.0; 3];
+    evaluate_batch(exp.as_ref(), &BatchArgs::empty(), &mut output).unwrap();
+    assert_eq!(output, [2.5, 2.5, 2.5]);
+}
+
+#[test]
+fn test_batch_columns() {
+    let x = [1.0, 2.0, 3.0, 4.0];
+    let y = [0.5, 1.0, 1.5, 2.0];
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    args.columns.insert("y".to_string(), &y);
+    let exp = parse_string("(x - y) * 2".to_string()).unwrap();
+    let mut output = [0.0; 4];
+    evaluate_batch(exp.as_ref(), &args, &mut output).unwrap();
+    assert_eq!(output, [1.0, 2.0, 3.0, 4.0]);
+}
+
+#[test]
+fn test_batch_multiple_chunks() {
+    let rows = BATCH_SIZE * 2 + 7;
+    let x: Vec<f64> = (0..rows).map(|i| i as f64).collect();
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    let exp = parse_string("x * 3 + 1".to_string()).unwrap();
+    let mut output = vec![0.0; rows];
+    evaluate_batch(exp.as_ref(), &args, &mut output).unwrap();
+
+    let mut row_args = ExpressionArgs::empty();
+    for (i, value) in output.iter().enumerate() {
+        row_args.variables.insert("x".to_string(), x[i]);
+        assert_eq!(*value, exp.as_ref().evaluate(&row_args).unwrap());
+    }
+}
+
+#[test]
+fn test_batch_column_length() {
+    let x = [1.0, 2.0];
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    let exp = parse_string("x + 1".to_string()).unwrap();
+    let mut output = [0.0; 3];
+    let result = evaluate_batch(exp.as_ref(), &args, &mut output);
+    assert_eq!(result.err().unwrap().to_string(), "Column 'x' has 2 rows, expected 3");
+}
+
+#[test]
+fn test_batch_unknown_variable() {
+    let exp = parse_string("x + 1".to_string()).unwrap();
+    let mut output = [0.0; 3];
+    let result = evaluate_batch(exp.as_ref(), &BatchArgs::empty(), &mut output);
+    assert_eq!(result.err().unwrap().to_string(), "Unknown variable 'x'");
+}
+
+#[test]
+fn test_batch_parallel() {
+    let rows = BATCH_SIZE * 5 + 3;
+    let x: Vec<f64> = (0..rows).map(|i| i as f64).collect();
+    let y: Vec<f64> = (0..rows).map(|i| (rows - i) as f64).collect();
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    args.columns.insert("y".to_string(), &y);
+    let exp = parse_string("x * y - x / 2".to_string()).unwrap();
+
+    let mut expected = vec![0.0; rows];
+    evaluate_batch(exp.as_ref(), &args, &mut expected).unwrap();
+    for threads in [1, 2, 4, 16] {
+        let mut output = vec![0.0; rows];
+        evaluate_batch_parallel(exp.as_ref(), &args, &mut output, threads).unwrap();
+        assert_eq!(output, expected);
+    }
+}
+
+#[test]
+fn test_batch_parallel_error() {
+    let exp = parse_string("x + 1".to_string()).unwrap();
+    let mut output = vec![0.0; BATCH_SIZE * 3];
+    let result = evaluate_batch_parallel(exp.as_ref(), &BatchArgs::empty(), &mut output, 3);
+    assert_eq!(result.err().unwrap().to_string(), "Unknown variable 'x'");
+}
+
+#[test]
+fn test_batch_settings() {
+    let x = [1.0, 0.0];
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    args.settings.non_finite = NonFinitePolicy::Substitute(0.0);
+    let exp = parse_string("2 / x".to_string()).unwrap();
+    let mut output = [0.0; 2];
+    evaluate_batch(exp.as_ref(), &args, &mut output).unwrap();
+    assert_eq!(output, [2.0, 0.0]);
+}
+
+#[test]
+fn test_batch_non_finite_error() {
+    let x = [1.0, f64::INFINITY, 0.0];
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x[..2]);
+    args.settings.non_finite = NonFinitePolicy::Error;
+    let exp = parse_string("x * 2 + 1".to_string()).unwrap();
+    let mut output = [0.0; 2];
+    evaluate_batch(exp.as_ref(), &args, &mut output).unwrap();
+    assert_eq!(output, [3.0, f64::INFINITY]);
+
+    args.columns.insert("x".to_string(), &x[1..]);
+    let exp = parse_string("2 / x".to_string()).unwrap();
+    let result = evaluate_batch(exp.as_ref(), &args, &mut output);
+    assert_eq!(result.err().unwrap().to_string(), "Arithmetic error (Division by zero)");
+}
+
+#[test]
+fn test_batch_parallel_error_type() {
+    let mut x = vec![1.0; BATCH_SIZE * 4];
+    x[BATCH_SIZE * 3 + 5] = 0.0;
+    let mut args = BatchArgs::empty();
+    args.columns.insert("x".to_string(), &x);
+    args.settings.non_finite = NonFinitePolicy::Error;
+    let exp = parse_string("1 / x".to_string()).unwrap();
+    let mut output = vec![0.0; x.len()];
+    let error = evaluate_batch_parallel(exp.as_ref(), &args, &mut output, 4).err().unwrap();
+    assert_eq!(error.downcast::<ArithmeticError>().unwrap().message, "Division by zero");
+}
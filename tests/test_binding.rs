@@ -0,0 +1,97 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+
+fn variable_args(variables: &[(&str, f64)]) -> ExpressionArgs {
+    let mut args = ExpressionArgs::empty();
+    for (name, value) in variables {
+        args.variables.insert(name.to_string(), *value);
+    }
+    args
+}
+
+#[test]
+fn test_let() {
+    let exp = parse_string("let r = x^2 + y^2 in sqrt(r) + r".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "let r = x ^ 2 + y ^ 2 in sqrt(r) + r");
+    assert_eq!(exp.evaluate(&variable_args(&[("x", 3.0), ("y", 4.0)])).unwrap(), 30.0);
+}
+
+#[test]
+fn test_let_shadows_variables() {
+    let args = variable_args(&[("x", 10.0)]);
+    assert_eq!(parse_string("let x = 2 in x * 3".to_string()).unwrap().evaluate(&args).unwrap(), 6.0);
+    assert_eq!(parse_string("let x = x + 1 in x".to_string()).unwrap().evaluate(&args).unwrap(), 11.0);
+    assert_eq!(parse_string("(let x = 2 in x) + x".to_string()).unwrap().evaluate(&args).unwrap(), 12.0);
+}
+
+#[test]
+fn test_nested_let() {
+    let exp = parse_string("let a = 2 in let b = a * 3 in a + b".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "let a = 2 in let b = a * 3 in a + b");
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).unwrap(), 8.0);
+    let exp = parse_string("let index = sin(0) + 1 in index + max(index)".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "let index = sin(0) + 1 in index + max(index)");
+}
+
+#[test]
+fn test_scopes_reach_outer_names() {
+    let mut args = variable_args(&[("x", 10.0)]);
+    args.lists.insert("xs".to_string(), vec![1.0, 2.0]);
+    args.functions.insert("half".to_string(), Arc::new(|x| x / 2.0));
+    let exp = parse_string("let xs = 3 in Σ(i, 1, xs, let a = half(i) in a * x + xs)".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&args).unwrap(), 39.0);
+    assert_eq!(parse_string("len(xs) + (let x = 1 in x)".to_string()).unwrap().evaluate(&args).unwrap(), 3.0);
+}
+
+#[test]
+fn test_let_evaluates_once() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = calls.clone();
+    let mut args = ExpressionArgs::empty();
+    args.functions.insert("costly".to_string(), Arc::new(move |x| {
+        counter.fetch_add(1, Ordering::Relaxed);
+        x * 2.0
+    }));
+    let exp = parse_string("let r = costly(4) in r * r + r".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&args).unwrap(), 72.0);
+    assert_eq!(calls.load(Ordering::Relaxed), 1);
+}
+
+#[test]
+fn test_let_errors() {
+    let error = |input: &str| parse_string(input.to_string()).err().unwrap().to_string();
+    assert_eq!(error("let r x in r"), "Parsing buffer error (Let binding without '=')");
+    assert_eq!(error("let 2r = 1 in r"), "Parsing buffer error (Invalid name in let binding)");
+    assert_eq!(error("let r = 1"), "Parsing buffer error (Let binding without 'in')");
+    assert!(parse_string("letter + 1".to_string()).is_ok());
+}
+
+#[test]
+fn test_sum_and_product() {
+    let args = variable_args(&[("n", 4.0), ("i", 100.0)]);
+    let exp = parse_string("sum(i, 1, n, i^2)".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "Σ(i, 1, n, i ^ 2)");
+    assert_eq!(exp.evaluate(&args).unwrap(), 30.0);
+    assert_eq!(parse_string(exp.to_string()).unwrap().evaluate(&args).unwrap(), 30.0);
+    let exp = parse_string("prod(k, 1, n, k) + i".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "Π(k, 1, n, k) + i");
+    assert_eq!(exp.evaluate(&args).unwrap(), 124.0);
+    assert_eq!(parse_string("sum(i, n, 1, i) + prod(i, n, 1, i)".to_string()).unwrap().evaluate(&args).unwrap(), 1.0);
+    assert_eq!(parse_string("sum(i, 1, 3, prod(j, 1, i, j))".to_string()).unwrap().evaluate(&args).unwrap(), 9.0);
+    assert!(!parse_string("sum(i, 1, m, i)".to_string()).unwrap().can_evaluate(&args));
+    assert!(parse_string("sum(i, 1, n, i)".to_string()).unwrap().can_evaluate(&args));
+}
+
+#[test]
+fn test_iteration_limit() {
+    let mut args = ExpressionArgs::empty();
+    args.settings.max_iterations = 100;
+    assert_eq!(parse_string("sum(i, 1, 100, 1)".to_string()).unwrap().evaluate(&args).unwrap(), 100.0);
+    let error = parse_string("sum(i, 1, 10^9, 1)".to_string()).unwrap().evaluate(&args).err().unwrap();
+    assert_eq!(error.to_string(), "Iteration limit of 100 exceeded");
+    let error = |input: &str| parse_string(input.to_string()).err().unwrap().to_string();
+    assert_eq!(error("prod(i, 1, 3)"), "Parsing buffer error (Sum and product need an index variable, two bounds and a body)");
+    assert_eq!(error("prod(2, 1, 3, i)"), "Parsing buffer error (Invalid index variable)");
+}
@@ -0,0 +1,80 @@
+use expression_parser::bytecode::{Instruction, Program};
+use expression_parser::enums::NonFinitePolicy;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::parser::parse_string;
+
+#[test]
+fn test_compile_instructions() {
+    let exp = parse_string("(x - 2) * y".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    assert_eq!(program.variables(), ["x", "y"]);
+    assert_eq!(program.instructions(), [
+        Instruction::Load(0),
+        Instruction::Push(2.0),
+        Instruction::Subtract,
+        Instruction::Load(1),
+        Instruction::Multiply,
+    ]);
+}
+
+#[test]
+fn test_evaluate_slots() {
+    let exp = parse_string("x / y + x".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    assert_eq!(program.evaluate(&[6.0, 3.0], &ExpressionSettings::default()).unwrap(), 8.0);
+    assert_eq!(program.evaluate(&[1.0, 4.0], &ExpressionSettings::default()).unwrap(), 1.25);
+}
+
+#[test]
+fn test_evaluate_reused_stack() {
+    let exp = parse_string("a + b + c".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    let settings = ExpressionSettings::default();
+    let mut stack = Vec::new();
+    for i in 0..10 {
+        let value = i as f64;
+        assert_eq!(program.evaluate_with_stack(&[value, 1.0, 2.0], &settings, &mut stack).unwrap(), value + 3.0);
+    }
+}
+
+#[test]
+fn test_compile_with_variables() {
+    let exp = parse_string("x - y".to_string()).unwrap();
+    let program = Program::compile_with_variables(exp.as_ref(), &["y", "x"]).unwrap();
+    assert_eq!(program.evaluate(&[1.0, 5.0], &ExpressionSettings::default()).unwrap(), 4.0);
+}
+
+#[test]
+fn test_compile_unknown_variable() {
+    let exp = parse_string("x - z".to_string()).unwrap();
+    let result = Program::compile_with_variables(exp.as_ref(), &["x"]);
+    assert_eq!(result.err().unwrap().to_string(), "Unknown variable 'z'");
+}
+
+#[test]
+fn test_evaluate_missing_slots() {
+    let exp = parse_string("x + y".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    let result = program.evaluate(&[1.0], &ExpressionSettings::default());
+    assert_eq!(result.err().unwrap().to_string(), "Expected 2 variable slots, got 1");
+}
+
+#[test]
+fn test_bind_args() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 2.0);
+    args.variables.insert("y".to_string(), 5.0);
+    let exp = parse_string("y * x - 1".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    let slots = program.bind(&args).unwrap();
+    assert_eq!(program.evaluate(&slots, &args.settings).unwrap(), exp.as_ref().evaluate(&args).unwrap());
+}
+
+#[test]
+fn test_evaluate_settings() {
+    let exp = parse_string("sqrt(x)".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    let settings = ExpressionSettings { non_finite: NonFinitePolicy::Error, ..ExpressionSettings::default() };
+    assert!(program.evaluate(&[-1.0], &ExpressionSettings::default()).unwrap().is_nan());
+    assert_eq!(program.evaluate(&[-1.0], &settings).err().unwrap().to_string(), "Arithmetic error (Square root of a negative number)");
+}
@@ -0,0 +1,55 @@
+use expression_parser::closure::compile;
+use expression_parser::enums::NonFinitePolicy;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::parser::parse_string;
+
+#[test]
+fn test_closure_constant() {
+    let exp = parse_string("5 / 2".to_string()).unwrap();
+    let function = compile(exp.as_ref(), &[]).unwrap();
+    assert_eq!(function(&[], &ExpressionSettings::default()).unwrap(), 2.5);
+}
+
+#[test]
+fn test_closure_variables() {
+    let exp = parse_string("(x - y) * 3".to_string()).unwrap();
+    let function = compile(exp.as_ref(), &["y", "x"]).unwrap();
+    assert_eq!(function(&[1.0, 5.0], &ExpressionSettings::default()).unwrap(), 12.0);
+    assert_eq!(function(&[2.0, 0.5], &ExpressionSettings::default()).unwrap(), -4.5);
+}
+
+#[test]
+fn test_closure_unknown_variable() {
+    let exp = parse_string("x + y".to_string()).unwrap();
+    let result = compile(exp.as_ref(), &["x"]);
+    assert_eq!(result.err().unwrap().to_string(), "Unknown variable 'y'");
+}
+
+#[test]
+fn test_closure_missing_slots() {
+    let exp = parse_string("x + y".to_string()).unwrap();
+    let function = compile(exp.as_ref(), &["x", "y"]).unwrap();
+    let result = function(&[1.0], &ExpressionSettings::default());
+    assert_eq!(result.err().unwrap().to_string(), "Expected 2 variable slots, got 1");
+}
+
+#[test]
+fn test_closure_from_args() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("a".to_string(), 3.0);
+    args.variables.insert("b".to_string(), 4.0);
+    let exp = parse_string("a * b + a".to_string()).unwrap();
+    let variables = ["a", "b"];
+    let function = compile(exp.as_ref(), &variables).unwrap();
+    let slots = args.values(&variables).unwrap();
+    assert_eq!(function(&slots, &args.settings).unwrap(), exp.as_ref().evaluate(&args).unwrap());
+}
+
+#[test]
+fn test_closure_settings() {
+    let exp = parse_string("1 / x".to_string()).unwrap();
+    let function = compile(exp.as_ref(), &["x"]).unwrap();
+    let settings = ExpressionSettings { non_finite: NonFinitePolicy::Substitute(-1.0), ..ExpressionSettings::default() };
+    assert_eq!(function(&[0.0], &ExpressionSettings::default()).unwrap(), f64::INFINITY);
+    assert_eq!(function(&[0.0], &settings).unwrap(), -1.0);
+}
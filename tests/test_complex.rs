@@ -0,0 +1,64 @@
+use expression_parser::complex::Complex;
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::number::Number;
+use expression_parser::parser::parse_string_as;
+
+fn evaluate(input: &str) -> Complex {
+    parse_string_as::<Complex>(input.to_string()).unwrap().evaluate(&ExpressionArgs::empty()).unwrap()
+}
+
+fn assert_close(actual: Complex, real: f64, imaginary: f64) {
+    assert!((actual.real - real).abs() < 1e-10 && (actual.imaginary - imaginary).abs() < 1e-10, "expected {} + {}i, got {}", real, imaginary, actual);
+}
+
+#[test]
+fn test_complex_literal() {
+    assert_eq!(Complex::parse_literal("3i").unwrap(), Complex::new(0.0, 3.0));
+    assert_eq!(Complex::parse_literal("-2.5i").unwrap(), Complex::new(0.0, -2.5));
+    assert_eq!(evaluate("i"), Complex::new(0.0, 1.0));
+    assert_eq!(evaluate("2 + 4i"), Complex::new(2.0, 4.0));
+    assert!(Complex::parse_literal("2ii").is_err());
+}
+
+#[test]
+fn test_imaginary_literal_outside_complex_mode() {
+    let error = parse_string_as::<f64>("3i".to_string()).err().unwrap();
+    assert_eq!(error.to_string(), "Error at char 'i' at index 1 (Imaginary literals need complex mode)");
+    assert!(parse_string_as::<f64>("3 * i".to_string()).is_ok());
+}
+
+#[test]
+fn test_complex_to_string() {
+    assert_eq!(evaluate("2 + 4i").to_string(), "2 + 4i");
+    assert_eq!(evaluate("2 - 4i").to_string(), "2 - 4i");
+    assert_eq!(evaluate("3i * 2").to_string(), "6i");
+    assert_eq!(evaluate("i * i").to_string(), "-1");
+}
+
+#[test]
+fn test_complex_arithmetic() {
+    assert_eq!(evaluate("(1 + 2i) * (3 - i)"), Complex::new(5.0, 5.0));
+    assert_close(evaluate("(5 + 5i) / (3 - i)"), 1.0, 2.0);
+}
+
+#[test]
+fn test_complex_sqrt_negative() {
+    assert_eq!(evaluate("sqrt(-1)"), Complex::new(0.0, 1.0));
+    assert_eq!(evaluate("sqrt(-4)").to_string(), "2i");
+}
+
+#[test]
+fn test_complex_power_negative_base() {
+    assert_eq!(evaluate("-8 ^ 2"), Complex::new(64.0, 0.0));
+    assert_close(evaluate("-4 ^ 0.5"), 0.0, 2.0);
+    assert_close(evaluate("i ^ 2"), -1.0, 0.0);
+}
+
+#[test]
+fn test_complex_functions() {
+    assert_close(evaluate("exp(3.141592653589793i)"), -1.0, 0.0);
+    assert_close(evaluate("ln(-1)"), 0.0, std::f64::consts::PI);
+    assert_close(evaluate("abs(3 + 4i)"), 5.0, 0.0);
+    assert_close(evaluate("sin(i)"), 0.0, 1f64.sinh());
+    assert_close(evaluate("cos(i)"), 1f64.cosh(), 0.0);
+}
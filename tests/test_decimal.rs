@@ -0,0 +1,100 @@
+use std::cmp::Ordering;
+use expression_parser::decimal::Decimal;
+use expression_parser::enums::{InexactDivision, RoundingMode};
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::number::Number;
+use expression_parser::parser::parse_string_as;
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, settings: ExpressionSettings) -> Result<Decimal, String> {
+    let mut args = ExpressionArgs::empty();
+    args.settings = settings;
+    parse_string_as::<Decimal>(input.to_string()).unwrap().evaluate(&args).map_err(|e| e.to_string())
+}
+
+fn settings(decimal_scale: u32, rounding_mode: RoundingMode, inexact_division: InexactDivision) -> ExpressionSettings {
+    ExpressionSettings { decimal_scale, rounding_mode, inexact_division, ..ExpressionSettings::default() }
+}
+
+#[test]
+fn test_decimal_exact_sum() {
+    let result = evaluate("0.1 + 0.2", ExpressionSettings::default()).unwrap();
+    assert_eq!(result, Decimal::parse_literal("0.3").unwrap());
+    assert_eq!(result.to_string(), "0.30");
+}
+
+#[test]
+fn test_decimal_scale() {
+    let result = evaluate("1.5 * 1.5", settings(4, RoundingMode::HalfEven, InexactDivision::Error)).unwrap();
+    assert_eq!(result.to_string(), "2.2500");
+    assert_eq!(result.scale(), 4);
+}
+
+#[test]
+fn test_decimal_rounding_modes() {
+    let cases = [
+        (RoundingMode::HalfEven, "0.12", "-0.12"),
+        (RoundingMode::HalfUp, "0.13", "-0.13"),
+        (RoundingMode::HalfDown, "0.12", "-0.12"),
+        (RoundingMode::Up, "0.13", "-0.13"),
+        (RoundingMode::Down, "0.12", "-0.12"),
+        (RoundingMode::Ceiling, "0.13", "-0.12"),
+        (RoundingMode::Floor, "0.12", "-0.13"),
+    ];
+    for (rounding_mode, positive, negative) in cases {
+        let options = settings(2, rounding_mode, InexactDivision::Round);
+        assert_eq!(evaluate("0.25 * 0.5", options.clone()).unwrap().to_string(), positive, "{:?}", rounding_mode);
+        assert_eq!(evaluate("-0.25 * 0.5", options).unwrap().to_string(), negative, "{:?}", rounding_mode);
+    }
+}
+
+#[test]
+fn test_decimal_half_even() {
+    let options = settings(0, RoundingMode::HalfEven, InexactDivision::Round);
+    assert_eq!(evaluate("2.5 + 0", options.clone()).unwrap().to_string(), "2");
+    assert_eq!(evaluate("3.5 + 0", options).unwrap().to_string(), "4");
+}
+
+#[test]
+fn test_decimal_inexact_division() {
+    assert_eq!(evaluate("10 / 4", ExpressionSettings::default()).unwrap().to_string(), "2.50");
+    assert_eq!(evaluate("10 / 3", ExpressionSettings::default()).err().unwrap(), "Arithmetic error (Decimal division result does not fit the scale)");
+    let options = settings(3, RoundingMode::HalfUp, InexactDivision::Round);
+    assert_eq!(evaluate("10 / 3", options.clone()).unwrap().to_string(), "3.333");
+    assert_eq!(evaluate("2 / 3", options).unwrap().to_string(), "0.667");
+}
+
+#[test]
+fn test_decimal_errors() {
+    assert_eq!(evaluate("1 / 0", ExpressionSettings::default()).err().unwrap(), "Arithmetic error (Division by zero)");
+    let huge = "9".repeat(30);
+    assert_eq!(evaluate(&format!("{} * {}", huge, huge), ExpressionSettings::default()).err().unwrap(), "Arithmetic error (Decimal overflow)");
+}
+
+#[test]
+fn test_decimal_literal() {
+    let value = Decimal::parse_literal("-21.250").unwrap();
+    assert_eq!((value.units(), value.scale()), (-21250, 3));
+    assert_eq!(value.to_string(), "-21.250");
+    assert_eq!(Decimal::new(5, 3).to_string(), "0.005");
+    assert!(Decimal::parse_literal("1.2.3").is_err());
+}
+
+#[test]
+fn test_decimal_comparison_below_scale() {
+    let evaluate = |input: &str| parse_string_as::<Decimal>(input.to_string()).unwrap().evaluate_value(&ExpressionArgs::empty()).map_err(|e| e.to_string());
+    assert_eq!(evaluate("0.001 == 0.002"), Ok(Value::Boolean(false)));
+    assert_eq!(evaluate("0.001 < 0.002"), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("0.0010 == 0.001"), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("-1.25 < -1.2"), Ok(Value::Boolean(true)));
+    let settings = ExpressionSettings::default();
+    let large = Decimal::parse_literal("170141183460469231731687303715884105727").unwrap();
+    let small = Decimal::parse_literal("-0.00000000000000000000000000000000000001").unwrap();
+    assert_eq!(large.compare(&small, &settings).unwrap(), Ordering::Greater);
+}
+
+#[test]
+fn test_decimal_index() {
+    assert_eq!(evaluate("let xs = [1.5, 2.5] in xs[0.50 + 0.5]", ExpressionSettings::default()).unwrap().to_string(), "2.5");
+    assert_eq!(evaluate("let xs = [1.5, 2.5] in xs[0.5]", ExpressionSettings::default()).err().unwrap(), "Index 0.5 is out of range for a list of 2 values");
+}
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+use expression_parser::definition::FunctionRegistry;
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+
+fn evaluate(input: &str, args: &ExpressionArgs) -> Result<f64, String> {
+    parse_string(input.to_string()).unwrap().evaluate(args).map_err(|e| e.to_string())
+}
+
+fn define(definitions: &str) -> Result<ExpressionArgs, String> {
+    let mut args = ExpressionArgs::empty();
+    args.definitions.define(definitions).map_err(|e| e.to_string())?;
+    Ok(args)
+}
+
+#[test]
+fn test_user_function() {
+    let args = define("f(x, y) = x^2 + y\ng(x) = f(x, 1) * 2").unwrap();
+    assert_eq!(evaluate("f(3, 4)", &args), Ok(13.0));
+    assert_eq!(evaluate("g(2) + f(0, 0.5)", &args), Ok(10.5));
+    assert_eq!(args.definitions.get("f").unwrap().to_string(), "f(x, y) = x ^ 2 + y");
+    assert_eq!(parse_string("f(3, 4)".to_string()).unwrap().to_string(), "f(3, 4)");
+}
+
+#[test]
+fn test_user_function_scope() {
+    let mut args = define("scaled(x) = x * factor\nzero() = 0").unwrap();
+    args.variables.insert("factor".to_string(), 2.0);
+    args.variables.insert("x".to_string(), 10.0);
+    assert_eq!(evaluate("scaled(1)", &args), Err("Unknown variable 'factor'".to_string()));
+    assert_eq!(evaluate("zero() + x", &args), Ok(10.0));
+}
+
+#[test]
+fn test_calls_to_closures() {
+    let mut args = define("f(x) = g(x) + 1").unwrap();
+    assert_eq!(evaluate("f(2)", &args), Err("Unknown function 'g'".to_string()));
+    args.functions.insert("g".to_string(), Arc::new(|x| x * 10.0));
+    assert_eq!(evaluate("f(2)", &args), Ok(21.0));
+}
+
+#[test]
+fn test_recursion() {
+    let mut args = define("fact(n) = if(n <= 1, 1, n * fact(n - 1))").unwrap();
+    assert_eq!(evaluate("fact(5)", &args), Ok(120.0));
+    args.settings.max_call_depth = 3;
+    assert_eq!(evaluate("fact(3)", &args), Ok(6.0));
+    assert_eq!(evaluate("fact(4)", &args), Err("Call depth limit of 3 exceeded in 'fact'".to_string()));
+}
+
+#[test]
+fn test_definition_errors() {
+    assert_eq!(define("f(x) = sqrt(x, 2)").err().unwrap(), "Wrong number of arguments for 'sqrt' (expected 1, got 2)");
+    assert_eq!(define("f(x) = x\ng(x) = f(x, x)").err().unwrap(), "Wrong number of arguments for 'f' (expected 1, got 2)");
+    assert_eq!(define("f(x) = g(x)\ng(x) = h(x)\nh(x) = f(x)").err().unwrap(), "Functions call each other in a cycle: f -> g -> h -> f");
+    assert_eq!(define("f x = x").err().unwrap(), "Parsing buffer error (Invalid function definition)");
+    assert_eq!(define("f(x, x) = x").err().unwrap(), "Parsing buffer error (Duplicate parameter name)");
+    assert_eq!(define("sqrt(x) = x").err().unwrap(), "Parsing buffer error (Built-in functions cannot be redefined)");
+    for name in ["if", "piecewise", "not", "prod", "Σ", "Π"] {
+        assert_eq!(define(&format!("{}(x) = x", name)).err().unwrap(), "Parsing buffer error (Reserved words cannot name a function)");
+    }
+    assert_eq!(define("f(x) = x\nf(y) = y").err().unwrap(), "Parsing buffer error (Function defined twice)");
+}
+
+#[test]
+fn test_failed_definition_is_not_added() {
+    let mut registry = FunctionRegistry::<f64>::new();
+    registry.define("f(x) = x == 1").unwrap();
+    assert!(registry.define("g(x) = f(x)\nh(x) = f(x, x)").is_err());
+    assert!(registry.get("g").is_none());
+    assert!(registry.get("f").is_some());
+}
+
+#[test]
+fn test_call_argument_count() {
+    let args = define("f(x, y) = x + y").unwrap();
+    assert_eq!(evaluate("f(1)", &args), Err("Wrong number of arguments for 'f' (expected 2, got 1)".to_string()));
+    assert_eq!(evaluate("sqrt(1, 2)", &args), Err("Wrong number of arguments for 'sqrt' (expected 1, got 2)".to_string()));
+}
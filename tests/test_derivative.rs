@@ -0,0 +1,44 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+
+fn derivative(input: &str) -> Result<String, String> {
+    let exp = parse_string(input.to_string()).unwrap();
+    exp.differentiate("x").map(|derivative| derivative.to_string()).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_derivative_rules() {
+    assert_eq!(derivative("3 * x ^ 2 + 2 * x - 7").unwrap(), "3 * (2 * x) + 2");
+    assert_eq!(derivative("x * y").unwrap(), "y");
+    assert_eq!(derivative("pi * x").unwrap(), "pi");
+    assert_eq!(derivative("1 / x").unwrap(), "-1 / x ^ 2");
+    assert_eq!(derivative("x ^ -1").unwrap(), "-1 * x ^ -2");
+    assert_eq!(derivative("2 ^ x").unwrap(), "2 ^ x * ln(2)");
+    assert_eq!(derivative("x ^ x").unwrap(), "x ^ x * (ln(x) + x / x)");
+    assert_eq!(derivative("exp(x) / x").unwrap(), "(exp(x) * x - exp(x)) / x ^ 2");
+}
+
+#[test]
+fn test_derivative_chain_rule() {
+    assert_eq!(derivative("sin(x ^ 2)").unwrap(), "cos(x ^ 2) * (2 * x)");
+    assert_eq!(derivative("cos(2 * x)").unwrap(), "-1 * sin(2 * x) * 2");
+    assert_eq!(derivative("sqrt(x)").unwrap(), "1 / (2 * sqrt(x))");
+    assert_eq!(derivative("ln(x) - tan(x)").unwrap(), "1 / x - 1 / cos(x) ^ 2");
+    assert_eq!(derivative("abs(x)").unwrap(), "x / abs(x)");
+}
+
+#[test]
+fn test_derivative_parses_back() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 0.7);
+    for (input, expected) in [("(x + 1) * (x - 1)", 1.4), ("x / (x - 2)", -2.0 / 1.69), ("x ^ 3 / 3 - 2 ^ x", 0.49 - 0.7f64.exp2() * 2f64.ln())] {
+        let derivative = parse_string(derivative(input).unwrap()).unwrap();
+        assert!((derivative.evaluate(&args).unwrap() - expected).abs() < 1e-12, "{}", input);
+    }
+}
+
+#[test]
+fn test_not_differentiable() {
+    assert_eq!(derivative("x % 2").err().unwrap(), "Modulo has no symbolic derivative");
+    assert_eq!(derivative("f(x)").err().unwrap(), "Function has no symbolic derivative");
+}
@@ -0,0 +1,37 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+
+#[test]
+fn test_dot_scalar() {
+    let exp = parse_string("21".to_string()).unwrap();
+    assert_eq!(exp.to_dot(), "digraph expression {\n    ordering=out;\n    n0 [label=\"ScalarValue\\n21\"];\n}\n");
+}
+
+#[test]
+fn test_dot_chain() {
+    let exp = parse_string("4 - 2 + 1".to_string()).unwrap();
+    let expected = concat!(
+        "digraph expression {\n",
+        "    ordering=out;\n",
+        "    n0 [label=\"Addition\"];\n",
+        "    n1 [label=\"Subtraction\"];\n",
+        "    n2 [label=\"ScalarValue\\n4\"];\n",
+        "    n1 -> n2;\n",
+        "    n3 [label=\"ScalarValue\\n2\"];\n",
+        "    n1 -> n3;\n",
+        "    n0 -> n1;\n",
+        "    n4 [label=\"ScalarValue\\n1\"];\n",
+        "    n0 -> n4;\n",
+        "}\n",
+    );
+    assert_eq!(exp.to_dot(), expected);
+}
+
+#[test]
+fn test_dot_with_values() {
+    let exp = parse_string("5 / 2".to_string()).unwrap();
+    let dot = exp.to_dot_with_values(&ExpressionArgs::empty());
+    assert!(dot.contains("n0 [label=\"Division\\n= 2.5\"];"));
+    assert!(dot.contains("n1 [label=\"ScalarValue\\n5\\n= 5\"];"));
+    assert!(dot.contains("n2 [label=\"ScalarValue\\n2\\n= 2\"];"));
+}
@@ -1,4 +1,5 @@
 use std::error::Error;
+use std::sync::Arc;
 use expression_parser::expression::{Expression, ExpressionArgs, ExpressionSettings};
 use expression_parser::parser::parse_string;
 
@@ -19,13 +20,13 @@ impl Setup {
     }
 
     fn assert_exp_result(&self, result: Result<Box<dyn Expression>, Box<dyn Error>>, expected_value: f64, expected_repr: &str) {
-        assert!(!result.is_err());
+        assert!(result.is_ok());
         let result_exp = result.unwrap();
 
         let actual_repr = result_exp.as_ref().to_string();
         assert_eq!(expected_repr, actual_repr, "repr: expected = {:?}, actual = {:?}", actual_repr, actual_repr);
 
-        let actual_value = result_exp.as_ref().evaluate(&ExpressionArgs::empty());
+        let actual_value = result_exp.as_ref().evaluate(&ExpressionArgs::empty()).unwrap();
         assert!(self.comp_with_delta(expected_value, actual_value), "val: expected = {:?}, actual = {:?}", expected_value, actual_value);
     }
 }
@@ -55,7 +56,7 @@ fn test_parse_value_neg_int() {
 fn test_parse_value_float() {
     let setup = Setup::new();
     let result = parse_string("21.25".to_string());
-    assert!(!result.is_err());
+    assert!(result.is_ok());
     setup.assert_exp_result(result, 21.25f64, "21.25");
 }
 
@@ -63,7 +64,7 @@ fn test_parse_value_float() {
 fn test_parse_value_neg_float() {
     let setup = Setup::new();
     let result = parse_string("-21.25".to_string());
-    assert!(!result.is_err());
+    assert!(result.is_ok());
     setup.assert_exp_result(result, -21.25f64, "-21.25");
 }
 
@@ -207,3 +208,77 @@ fn test_divide_float() {
     setup.assert_exp_result(result, 1.90909090909f64, "4.2 / 2.2");
 }
 
+
+#[test]
+fn test_variable() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 4.0);
+    let exp = parse_string("x * 2 + 1".to_string()).unwrap();
+    assert_eq!(exp.as_ref().to_string(), "x * 2 + 1");
+    assert!(exp.as_ref().can_evaluate(&args));
+    assert_eq!(exp.as_ref().evaluate(&args).unwrap(), 9.0);
+}
+
+#[test]
+fn test_variable_undefined() {
+    let exp = parse_string("x1".to_string()).unwrap();
+    assert!(!exp.as_ref().can_evaluate(&ExpressionArgs::empty()));
+}
+
+#[test]
+fn test_precedence() {
+    let setup = Setup::new();
+    setup.assert_exp_result(parse_string("1 + 2 * 3".to_string()), 7.0f64, "1 + 2 * 3");
+    setup.assert_exp_result(parse_string("8 - 6 / 2 - 1".to_string()), 4.0f64, "8 - 6 / 2 - 1");
+    setup.assert_exp_result(parse_string("2 * 3 ^ 2".to_string()), 18.0f64, "2 * 3 ^ 2");
+}
+
+#[test]
+fn test_power_right_associative() {
+    let setup = Setup::new();
+    setup.assert_exp_result(parse_string("2 ^ 3 ^ 2".to_string()), 512.0f64, "2 ^ 3 ^ 2");
+}
+
+#[test]
+fn test_brackets() {
+    let setup = Setup::new();
+    setup.assert_exp_result(parse_string("(1 + 2) * 3".to_string()), 9.0f64, "(1 + 2) * 3");
+    setup.assert_exp_result(parse_string("2 * ((4 - 1) / (2 + 1))".to_string()), 2.0f64, "2 * ((4 - 1) / (2 + 1))");
+}
+
+#[test]
+fn test_brackets_unbalanced() {
+    assert_eq!(parse_string("(1 + 2".to_string()).err().unwrap().to_string(), "Parsing buffer error (Unclosed bracket)");
+    assert_eq!(parse_string("1 + 2)".to_string()).err().unwrap().to_string(), "Error at char ')' at index 5 (Unmatched closing bracket)");
+}
+
+#[test]
+fn test_negative_operand() {
+    let setup = Setup::new();
+    setup.assert_exp_result(parse_string("2 / -4".to_string()), -0.5f64, "2 / -4");
+    assert!(parse_string("2 * * 4".to_string()).is_err());
+}
+
+#[test]
+fn test_function() {
+    let setup = Setup::new();
+    setup.assert_exp_result(parse_string("sqrt(16) + abs(2 - 5)".to_string()), 7.0f64, "sqrt(16) + abs(2 - 5)");
+
+    let mut args = ExpressionArgs::empty();
+    args.functions.insert("double".to_string(), Arc::new(|x| x * 2.0));
+    let exp = parse_string("double(3)".to_string()).unwrap();
+    assert_eq!(exp.as_ref().evaluate(&args).unwrap(), 6.0);
+    assert!(!exp.as_ref().can_evaluate(&ExpressionArgs::empty()));
+    assert_eq!(exp.as_ref().evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Unknown function 'double'");
+}
+
+#[test]
+fn test_settings_per_args() {
+    let exp = parse_string("1 / x".to_string()).unwrap();
+    let mut strict = ExpressionArgs::empty();
+    strict.variables.insert("x".to_string(), 1e-6);
+    let mut tolerant = strict.clone();
+    tolerant.settings.f64_delta = 1e-3;
+    assert!(exp.as_ref().can_evaluate(&strict));
+    assert!(!exp.as_ref().can_evaluate(&tolerant));
+}
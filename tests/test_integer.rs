@@ -0,0 +1,65 @@
+use expression_parser::enums::IntegerDivision;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::parser::{parse_string, parse_string_as};
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, integer_division: IntegerDivision) -> Result<i64, String> {
+    let mut args = ExpressionArgs::empty();
+    args.settings = ExpressionSettings { integer_division, ..ExpressionSettings::default() };
+    parse_string_as::<i64>(input.to_string()).unwrap().evaluate(&args).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_integer_arithmetic() {
+    assert_eq!(evaluate("7 + 3 * 2 - 4", IntegerDivision::Truncate), Ok(9));
+    assert_eq!(evaluate("2 ^ 10", IntegerDivision::Truncate), Ok(1024));
+    assert_eq!(evaluate("2 ^ -1", IntegerDivision::Truncate).err().unwrap(), "Arithmetic error (Integer exponent must be non-negative)");
+}
+
+#[test]
+fn test_integer_division_modes() {
+    assert_eq!(evaluate("7 / 2", IntegerDivision::Truncate), Ok(3));
+    assert_eq!(evaluate("-7 / 2", IntegerDivision::Truncate), Ok(-3));
+    assert_eq!(evaluate("-7 / 2", IntegerDivision::Floor), Ok(-4));
+    assert_eq!(evaluate("7 / -2", IntegerDivision::Floor), Ok(-4));
+    assert_eq!(evaluate("-8 / 2", IntegerDivision::Floor), Ok(-4));
+}
+
+#[test]
+fn test_integer_modulo() {
+    assert_eq!(evaluate("17 % 5", IntegerDivision::Truncate), Ok(2));
+    assert_eq!(evaluate("-7 % 3", IntegerDivision::Truncate), Ok(-1));
+    assert_eq!(evaluate("-7 % 3", IntegerDivision::Floor), Ok(2));
+    assert_eq!(evaluate("7 % -3", IntegerDivision::Floor), Ok(-2));
+    assert_eq!(evaluate("1 + 10 % 4", IntegerDivision::Truncate), Ok(3));
+    assert_eq!(evaluate("5 % 0", IntegerDivision::Truncate).err().unwrap(), "Arithmetic error (Division by zero)");
+}
+
+#[test]
+fn test_integer_overflow() {
+    assert_eq!(evaluate("9223372036854775807 + 1", IntegerDivision::Truncate).err().unwrap(), "Arithmetic error (Integer overflow)");
+    assert_eq!(evaluate("-9223372036854775808 / -1", IntegerDivision::Truncate).err().unwrap(), "Arithmetic error (Integer overflow)");
+    assert_eq!(evaluate("-9223372036854775808 % -1", IntegerDivision::Truncate), Ok(0));
+    let wide = parse_string_as::<i128>("9223372036854775807 + 1".to_string()).unwrap();
+    assert_eq!(wide.evaluate(&ExpressionArgs::empty()).unwrap(), 9223372036854775808);
+}
+
+#[test]
+fn test_integer_rejects_fractional_literal() {
+    let result = parse_string_as::<i64>("1 + 2.5".to_string());
+    assert_eq!(result.err().unwrap().to_string(), "Error at char '.' at index 5 (Fractional literal in integer mode)");
+}
+
+#[test]
+fn test_float_modulo() {
+    let exp = parse_string("7.5 % 2".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).unwrap(), 1.5);
+}
+
+#[test]
+fn test_integer_comparison_extremes() {
+    let evaluate = |input: &str| parse_string_as::<i64>(input.to_string()).unwrap().evaluate_value(&ExpressionArgs::empty()).map_err(|e| e.to_string());
+    assert_eq!(evaluate("9223372036854775807 > 0 - 1"), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("9223372036854775807 == 0 - 2"), Ok(Value::Boolean(false)));
+    assert_eq!(evaluate("0 - 9223372036854775807 - 1 < 9223372036854775807"), Ok(Value::Boolean(true)));
+}
@@ -0,0 +1,78 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::interval::Interval;
+use expression_parser::number::Number;
+use expression_parser::parser::parse_string_as;
+
+fn evaluate(input: &str, variables: &[(&str, f64, f64)]) -> Result<Interval, String> {
+    let mut args = ExpressionArgs::empty();
+    for (name, lower, upper) in variables {
+        args.variables.insert(name.to_string(), Interval::new(*lower, *upper).unwrap());
+    }
+    parse_string_as::<Interval>(input.to_string()).unwrap().evaluate(&args).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_interval_variables() {
+    let result = evaluate("x * y - x", &[("x", 1.0, 2.0), ("y", -1.0, 3.0)]).unwrap();
+    assert_eq!(result, Interval::new(-4.0, 5.0).unwrap());
+    assert_eq!(result.to_string(), "[-4, 5]");
+}
+
+#[test]
+fn test_interval_outward_rounding() {
+    let result = evaluate("0.1 + 0.2", &[]).unwrap();
+    assert!(result.contains(0.30000000000000004) && result.lower() < 0.3 && result.upper() > 0.3);
+    assert!(result.width() < 1e-15);
+
+    let third = evaluate("1 / 3", &[]).unwrap();
+    assert_eq!(third.upper(), third.lower().next_up());
+    assert_eq!(evaluate("1 / 4", &[]).unwrap(), Interval::point(0.25));
+}
+
+#[test]
+fn test_interval_division_by_zero() {
+    assert_eq!(evaluate("1 / x", &[("x", -1.0, 2.0)]).unwrap(), Interval::new(f64::NEG_INFINITY, f64::INFINITY).unwrap());
+    assert_eq!(evaluate("1 / x", &[("x", 0.0, 2.0)]).unwrap(), Interval::new(0.5, f64::INFINITY).unwrap());
+    assert_eq!(evaluate("-1 / x", &[("x", 0.0, 2.0)]).unwrap(), Interval::new(f64::NEG_INFINITY, -0.5).unwrap());
+    assert_eq!(evaluate("2 / x", &[("x", -4.0, 0.0)]).unwrap(), Interval::new(f64::NEG_INFINITY, -0.5).unwrap());
+    assert_eq!(evaluate("1 / x", &[("x", 0.0, 0.0)]).err().unwrap(), "Arithmetic error (Division by zero)");
+}
+
+#[test]
+fn test_interval_power() {
+    assert_eq!(evaluate("x ^ 2", &[("x", -2.0, 3.0)]).unwrap(), Interval::new(0.0, 9.0).unwrap());
+    assert_eq!(evaluate("x ^ 3", &[("x", -2.0, 3.0)]).unwrap(), Interval::new(-8.0, 27.0).unwrap());
+    assert_eq!(evaluate("x ^ 0", &[("x", -2.0, 3.0)]).unwrap(), Interval::new(1.0, 1.0).unwrap());
+    assert_eq!(evaluate("x ^ -1", &[("x", 2.0, 4.0)]).unwrap(), Interval::new(0.25, 0.5).unwrap());
+    assert!(evaluate("x ^ 0.5", &[("x", 4.0, 9.0)]).unwrap().contains(3.0));
+}
+
+#[test]
+fn test_interval_functions() {
+    assert_eq!(evaluate("sqrt(x)", &[("x", 4.0, 9.0)]).unwrap(), Interval::new(2.0, 3.0).unwrap());
+    assert_eq!(evaluate("abs(x)", &[("x", -3.0, 2.0)]).unwrap(), Interval::new(0.0, 3.0).unwrap());
+    assert_eq!(evaluate("sin(x)", &[("x", 1.0, 2.0)]).unwrap().upper(), 1.0);
+    let cosine = evaluate("cos(x)", &[("x", 1.0, 4.0)]).unwrap();
+    assert_eq!(cosine.lower(), -1.0);
+    assert!(cosine.contains(1f64.cos()) && cosine.upper() < 0.55);
+    assert!(evaluate("tan(x)", &[("x", 1.0, 2.0)]).unwrap().upper().is_infinite());
+    assert!(evaluate("ln(x)", &[("x", -2.0, -1.0)]).is_err());
+}
+
+#[test]
+fn test_interval_literal() {
+    assert_eq!(Interval::parse_literal("3").unwrap(), Interval::point(3.0));
+    assert!(Interval::parse_literal("0.1").unwrap().lower() < 0.1);
+    assert!(Interval::new(2.0, 1.0).is_err());
+}
+
+#[test]
+fn test_interval_print_and_parse() {
+    let formula = parse_string_as::<Interval>("sqrt(2) + 0.1 * x".to_string()).unwrap();
+    assert_eq!(formula.to_string(), "sqrt(2) + 0.1 * x");
+    let reparsed = parse_string_as::<Interval>(formula.to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), Interval::point(3.0));
+    assert_eq!(reparsed.evaluate(&args).unwrap(), formula.evaluate(&args).unwrap());
+    assert_eq!(Interval::new(1.0, 2.0).unwrap().to_literal(), "interval(1, 2)");
+}
@@ -0,0 +1,19 @@
+use expression_parser::parser::parse_string;
+
+fn latex(input: &str) -> String {
+    parse_string(input.to_string()).unwrap().to_latex()
+}
+
+#[test]
+fn test_latex_series() {
+    assert_eq!(latex("sum(i, 1, n, i^2)"), "\\sum_{i=1}^{n} i^{2}");
+    assert_eq!(latex("prod(k, 1, n, k + 1) / 2"), "\\frac{\\prod_{k=1}^{n} \\left(k + 1\\right)}{2}");
+    assert_eq!(latex("Σ(i, 0, n - 1, 2 * i)"), "\\sum_{i=0}^{n - 1} 2 \\cdot i");
+}
+
+#[test]
+fn test_latex_arithmetic() {
+    assert_eq!(latex("(x + 1) / sqrt(y) - z"), "\\frac{\\left(x + 1\\right)}{\\sqrt{y}} - z");
+    assert_eq!(latex("sin(x) ^ 2 + abs(x) * f(x, 2)"), "\\sin\\left(x\\right)^{2} + \\left|x\\right| \\cdot \\operatorname{f}\\left(x, 2\\right)");
+    assert_eq!(latex("max([1, 2])"), "max([1, 2])");
+}
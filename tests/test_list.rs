@@ -0,0 +1,112 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::complex::Complex;
+use expression_parser::parser::{parse_string, parse_string_as};
+use expression_parser::rational::Rational;
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, args: &ExpressionArgs) -> Result<Value, String> {
+    parse_string(input.to_string()).unwrap().evaluate_value(args).map_err(|e| e.to_string())
+}
+
+fn list_args() -> ExpressionArgs {
+    let mut args = ExpressionArgs::empty();
+    args.lists.insert("xs".to_string(), vec![4.0, 1.0, 3.0, 2.0]);
+    args.variables.insert("k".to_string(), 2.0);
+    args
+}
+
+#[test]
+fn test_list_literal() {
+    let exp = parse_string("[1, 2 * 3, (4 + 5)]".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "[1, 2 * 3, (4 + 5)]");
+    assert_eq!(exp.evaluate_value(&ExpressionArgs::empty()).unwrap(), Value::List(vec![1.0, 6.0, 9.0]));
+    assert_eq!(evaluate("[]", &ExpressionArgs::empty()), Ok(Value::List(vec![])));
+    assert_eq!(evaluate("[1, [2]]", &ExpressionArgs::empty()), Err("Expected a number, got a list".to_string()));
+    assert_eq!(Value::<f64>::List(vec![1.0, 2.5]).to_string(), "[1, 2.5]");
+}
+
+#[test]
+fn test_broadcasting() {
+    let args = list_args();
+    assert_eq!(evaluate("xs * k + 1", &args), Ok(Value::List(vec![9.0, 3.0, 7.0, 5.0])));
+    assert_eq!(evaluate("10 - xs", &args), Ok(Value::List(vec![6.0, 9.0, 7.0, 8.0])));
+    assert_eq!(evaluate("xs + [1, 2, 3, 4]", &args), Ok(Value::List(vec![5.0, 3.0, 6.0, 6.0])));
+    assert_eq!(evaluate("sqrt(xs)", &args), Ok(Value::List(vec![2.0, 1.0, 3.0f64.sqrt(), 2.0f64.sqrt()])));
+    assert_eq!(evaluate("xs + [1, 2]", &args), Err("Incompatible shapes list of 4 and list of 2 in 'xs + [1, 2]' at 3..4".to_string()));
+    assert_eq!(evaluate("xs + (1 < 2)", &args), Err("Expected a number, got a boolean".to_string()));
+    assert_eq!(parse_string("xs + 1".to_string()).unwrap().evaluate(&args).err().unwrap().to_string(), "Expected a number, got a list");
+}
+
+#[test]
+fn test_aggregates() {
+    let args = list_args();
+    let number = |input: &str| parse_string(input.to_string()).unwrap().evaluate(&args).unwrap();
+    assert_eq!(number("sum(xs)"), 10.0);
+    assert_eq!(number("mean(xs)"), 2.5);
+    assert_eq!(number("median(xs)"), 2.5);
+    assert_eq!(number("median([5, 1, 3])"), 3.0);
+    assert_eq!(number("stdev([2, 4, 4, 4, 5, 5, 7, 9])"), (32.0f64 / 7.0).sqrt());
+    assert_eq!(number("min(xs)"), 1.0);
+    assert_eq!(number("max(xs, 7, k)"), 7.0);
+    assert_eq!(number("len(xs) + len([])"), 4.0);
+    assert_eq!(number("sum(xs * xs)"), 30.0);
+    assert_eq!(number("sum(1, 2, 3, 4)"), 10.0);
+    assert_eq!(number("sum(2 * k, xs, 1, k)"), 17.0);
+    assert_eq!(number("sum(k, 1, 3, k)"), 6.0);
+    assert_eq!(parse_string("mean(xs)".to_string()).unwrap().to_string(), "mean(xs)");
+}
+
+#[test]
+fn test_aggregate_errors() {
+    let args = list_args();
+    assert_eq!(evaluate("sum([])", &args), Ok(Value::Number(0.0)));
+    assert_eq!(evaluate("mean([])", &args), Err("Cannot take the mean of an empty list".to_string()));
+    assert_eq!(evaluate("max([])", &args), Err("Cannot take the max of an empty list".to_string()));
+    assert_eq!(evaluate("stdev([1])", &args), Err("Arithmetic error (Standard deviation needs at least two values)".to_string()));
+    assert_eq!(evaluate("min(1 < 2)", &args), Err("Expected a number, got a boolean".to_string()));
+}
+
+#[test]
+fn test_indexing() {
+    let args = list_args();
+    let exp = parse_string("xs[0] + xs[k + 1]".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "xs[0] + xs[k + 1]");
+    assert_eq!(exp.evaluate(&args).unwrap(), 6.0);
+    assert_eq!(evaluate("sum(i, 0, len(xs) - 1, xs[i] * i)", &args), Ok(Value::Number(13.0)));
+    assert_eq!(evaluate("xs[4]", &args), Err("Index 4 is out of range for a list of 4 values".to_string()));
+    assert_eq!(evaluate("xs[0.5]", &args), Err("Index 0.5 is out of range for a list of 4 values".to_string()));
+    assert_eq!(evaluate("k[0]", &args), Err("Expected a list, got a number".to_string()));
+    assert!(!parse_string("ys[0]".to_string()).unwrap().can_evaluate(&args));
+    assert_eq!(evaluate("let ys = [5, 6, 7] in ys[2]", &args), Ok(Value::Number(7.0)));
+    let error = parse_string("[1, 2, 3][1]".to_string()).err().unwrap();
+    assert_eq!(error.to_string(), "Error at char '[' at index 9 (Only named lists can be indexed)");
+}
+
+#[test]
+fn test_indexing_other_number_types() {
+    let index = |input: &str| {
+        let exp = parse_string_as::<Rational>(input.to_string()).unwrap();
+        exp.evaluate(&ExpressionArgs::empty()).map_err(|e| e.to_string())
+    };
+    assert_eq!(index("let ys = [5, 6, 7] in ys[4 / 2]"), Ok(Rational::new(7, 1).unwrap()));
+    assert!(index("let ys = [5, 6, 7] in ys[1 / 2]").is_err());
+    let exp = parse_string_as::<Complex>("let ys = [5, 6, 7] in ys[1 + 0i] + ys[i]".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Index 1i is out of range for a list of 3 values");
+}
+
+#[test]
+fn test_let_list() {
+    let args = list_args();
+    assert_eq!(evaluate("let k = [1, 2] in k * 2", &args), Ok(Value::List(vec![2.0, 4.0])));
+    assert_eq!(evaluate("let xs = 3 in xs + 1", &args), Ok(Value::Number(4.0)));
+    assert_eq!(evaluate("let ys = xs - mean(xs) in max(ys)", &args), Ok(Value::Number(1.5)));
+}
+
+#[test]
+fn test_list_parse_errors() {
+    let error = |input: &str| parse_string(input.to_string()).err().unwrap().to_string();
+    assert!(error("[1, 2)").contains("Mismatched closing bracket"));
+    assert!(error("(1 + 2]").contains("Mismatched closing bracket"));
+    assert!(error("[1, 2][0]").contains("Only named lists can be indexed"));
+    assert_eq!(error("[1, 2"), "Parsing buffer error (Unclosed bracket)");
+}
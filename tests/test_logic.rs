@@ -0,0 +1,145 @@
+use expression_parser::bytecode::Program;
+use expression_parser::enums::NonFinitePolicy;
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::{parse_string, parse_string_as};
+use expression_parser::rational::Rational;
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, args: &ExpressionArgs) -> Result<Value, String> {
+    parse_string(input.to_string()).unwrap().evaluate_value(args).map_err(|e| e.to_string())
+}
+
+fn boolean(input: &str) -> bool {
+    evaluate(input, &ExpressionArgs::empty()).unwrap() == Value::Boolean(true)
+}
+
+#[test]
+fn test_comparisons() {
+    assert!(boolean("1 < 2"));
+    assert!(!boolean("2 < 2"));
+    assert!(boolean("2 <= 2"));
+    assert!(boolean("3 > 2"));
+    assert!(boolean("2 >= 2"));
+    assert!(boolean("2 == 2"));
+    assert!(boolean("2 != 3"));
+    assert!(boolean("1 + 2 * 3 == 7"));
+}
+
+#[test]
+fn test_equality_tolerance() {
+    assert!(boolean("0.1 + 0.2 == 0.3"));
+    assert!(!boolean("0.1 + 0.2 != 0.3"));
+    assert!(!boolean("0.1 + 0.2 > 0.3"));
+    assert!(boolean("0.1 + 0.2 <= 0.3"));
+
+    let mut args = ExpressionArgs::empty();
+    args.settings.f64_delta = 0.5;
+    assert_eq!(evaluate("1 == 1.25", &args), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("1 < 1.25", &args), Ok(Value::Boolean(false)));
+}
+
+#[test]
+fn test_logical_operators() {
+    assert!(boolean("1 < 2 and 2 < 3"));
+    assert!(!boolean("1 < 2 and 3 < 2"));
+    assert!(boolean("3 < 2 or 1 < 2"));
+    assert!(boolean("not 3 < 2"));
+    assert!(boolean("not 1 > 2 and 2 > 1"));
+    assert!(boolean("1 > 2 and 1 > 2 or 2 > 1"));
+    assert!(!boolean("1 > 2 and (1 > 2 or 2 > 1)"));
+    assert_eq!(parse_string("not x < 1 and y >= 2 or z != 3".to_string()).unwrap().to_string(), "not x < 1 and y >= 2 or z != 3");
+}
+
+#[test]
+fn test_short_circuit() {
+    assert!(!boolean("1 > 2 and x > 0"));
+    assert!(boolean("1 < 2 or x > 0"));
+    assert_eq!(evaluate("1 < 2 and x > 0", &ExpressionArgs::empty()), Err("Unknown variable 'x'".to_string()));
+}
+
+#[test]
+fn test_type_mismatch() {
+    let args = ExpressionArgs::empty();
+    assert_eq!(evaluate("(1 < 2) + 1", &args), Err("Expected a number, got a boolean".to_string()));
+    assert_eq!(evaluate("1 and 2 > 1", &args), Err("Expected a boolean, got a number".to_string()));
+    assert_eq!(parse_string("1 < 2".to_string()).unwrap().evaluate(&args).err().unwrap().to_string(), "Expected a number, got a boolean");
+}
+
+#[test]
+fn test_comparison_parse_errors() {
+    assert_eq!(parse_string("2 = 2".to_string()).err().unwrap().to_string(), "Parsing buffer error (Unknown operator)");
+    assert_eq!(parse_string("!x".to_string()).err().unwrap().to_string(), "Error at char '!' at index 0 (Operator at the start of a block)");
+    assert!(parse_string("x<-1".to_string()).is_ok());
+}
+
+#[test]
+fn test_exact_comparison() {
+    let exp = parse_string_as::<Rational>("1/3 + 1/3 < 2/3".to_string()).unwrap();
+    assert_eq!(exp.evaluate_value(&ExpressionArgs::empty()).unwrap(), Value::Boolean(false));
+}
+
+#[test]
+fn test_comparison_not_compilable() {
+    let exp = parse_string("x < 1".to_string()).unwrap();
+    assert_eq!(Program::compile(exp.as_ref()).err().unwrap().to_string(), "Comparison cannot be compiled");
+}
+
+#[test]
+fn test_conditional() {
+    let exp = parse_string("if(income > 1000, 0.2 * income, 0)".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "if(income > 1000, 0.2 * income, 0)");
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("income".to_string(), 2000.0);
+    assert_eq!(exp.evaluate(&args).unwrap(), 400.0);
+    args.variables.insert("income".to_string(), 500.0);
+    assert_eq!(exp.evaluate(&args).unwrap(), 0.0);
+    assert!(boolean("if(1 < 2, 3 > 2, 3 < 2)"));
+    args.variables.insert("x".to_string(), -3.0);
+    assert_eq!(evaluate("2 * if(x > 0, x, 0 - x) + 1", &args), Ok(Value::Number(7.0)));
+}
+
+#[test]
+fn test_conditional_is_lazy() {
+    let mut args = ExpressionArgs::empty();
+    args.settings.non_finite = NonFinitePolicy::Error;
+    args.variables.insert("x".to_string(), 0.0);
+    let exp = parse_string("if(x == 0, 0, 1 / x)".to_string()).unwrap();
+    assert!(exp.can_evaluate(&args));
+    assert_eq!(exp.evaluate(&args).unwrap(), 0.0);
+    args.variables.insert("x".to_string(), 4.0);
+    assert_eq!(exp.evaluate(&args).unwrap(), 0.25);
+}
+
+#[test]
+fn test_conditional_arguments() {
+    assert_eq!(parse_string("if(1 < 2, 3)".to_string()).err().unwrap().to_string(), "Parsing buffer error (Conditional needs a condition and two values)");
+    assert_eq!(evaluate("if(1, 2, 3)", &ExpressionArgs::empty()), Err("Expected a boolean, got a number".to_string()));
+}
+
+#[test]
+fn test_piecewise() {
+    let exp = parse_string("piecewise(x <= 1000, 0, x <= 5000, 0.1 * (x - 1000), 400 + 0.2 * (x - 5000))".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "piecewise(x <= 1000, 0, x <= 5000, 0.1 * (x - 1000), 400 + 0.2 * (x - 5000))");
+    let mut args = ExpressionArgs::empty();
+    for (income, tax) in [(500.0, 0.0), (3000.0, 200.0), (5000.0, 400.0), (6000.0, 600.0)] {
+        args.variables.insert("x".to_string(), income);
+        assert_eq!(exp.evaluate(&args).unwrap(), tax);
+    }
+}
+
+#[test]
+fn test_piecewise_derivative() {
+    let exp = parse_string("piecewise(x < 0, 0 - x, x < 2, x ^ 2, 4 * x - 4)".to_string()).unwrap();
+    assert_eq!(exp.differentiate("x").unwrap().to_string(), "piecewise(x < 0, -1, x < 2, 2 * x, 4)");
+    assert_eq!(exp.differentiate("y").unwrap().to_string(), "piecewise(x < 0, 0, x < 2, 0, 0)");
+}
+
+#[test]
+fn test_piecewise_no_match() {
+    let exp = parse_string("piecewise(x < 0, 0 - 1, x > 0, 1)".to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 0.0);
+    assert!(!exp.can_evaluate(&args));
+    assert_eq!(exp.evaluate(&args).err().unwrap().to_string(), "No arm of 'piecewise(x < 0, 0 - 1, x > 0, 1)' matches");
+    assert_eq!(parse_string("piecewise(1)".to_string()).err().unwrap().to_string(), "Parsing buffer error (Piecewise needs at least one condition and value)");
+}
@@ -0,0 +1,102 @@
+use expression_parser::errors::{NotSquare, ShapeMismatch};
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::matrix::Matrix;
+use expression_parser::parser::{parse_string, parse_string_as};
+use expression_parser::rational::Rational;
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, args: &ExpressionArgs) -> Result<Value, String> {
+    parse_string(input.to_string()).unwrap().evaluate_value(args).map_err(|e| e.to_string())
+}
+
+fn matrix(rows: Vec<Vec<f64>>) -> Value {
+    Value::Matrix(Matrix::from_rows(rows).unwrap())
+}
+
+fn assert_close(actual: Result<Value, String>, expected: Value) {
+    let (actual, expected) = (actual.unwrap().into_matrix().unwrap(), expected.into_matrix().unwrap());
+    assert_eq!((actual.rows(), actual.columns()), (expected.rows(), expected.columns()));
+    assert!(actual.values().iter().zip(expected.values()).all(|(actual, expected)| (actual - expected).abs() < 1e-12), "{} != {}", actual, expected);
+}
+
+fn matrix_args() -> ExpressionArgs {
+    let mut args = ExpressionArgs::empty();
+    args.matrices.insert("A".to_string(), Matrix::new(2, 2, vec![4.0, 7.0, 2.0, 6.0]).unwrap());
+    args.matrices.insert("B".to_string(), Matrix::from_rows(vec![vec![1.0, 2.0, 3.0], vec![4.0, 5.0, 6.0]]).unwrap());
+    args.lists.insert("v".to_string(), vec![1.0, 1.0]);
+    args
+}
+
+#[test]
+fn test_matrix_literal() {
+    let exp = parse_string("[[1, 2], [3, 4 + 1]]".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "[[1, 2], [3, 4 + 1]]");
+    assert_eq!(exp.evaluate_value(&ExpressionArgs::empty()).unwrap(), matrix(vec![vec![1.0, 2.0], vec![3.0, 5.0]]));
+    assert_eq!(matrix(vec![vec![1.0, 2.0], vec![3.0, 4.5]]).to_string(), "[[1, 2], [3, 4.5]]");
+    let matrix = Matrix::new(2, 3, vec![1.0, 2.0, 3.0, 4.0, 5.0, 6.0]).unwrap();
+    assert_eq!((matrix.rows(), matrix.columns(), matrix.get(1, 0)), (2, 3, Some(&4.0)));
+    assert_eq!(matrix.get(2, 0), None);
+    assert!(Matrix::new(2, 2, vec![1.0]).is_err());
+}
+
+#[test]
+fn test_matrix_product() {
+    let args = matrix_args();
+    assert_eq!(evaluate("A * B", &args), Ok(matrix(vec![vec![32.0, 43.0, 54.0], vec![26.0, 34.0, 42.0]])));
+    assert_eq!(evaluate("A * v", &args), Ok(Value::List(vec![11.0, 8.0])));
+    assert_eq!(evaluate("v * A", &args), Ok(Value::List(vec![6.0, 13.0])));
+    assert_eq!(evaluate("2 * A", &args), Ok(matrix(vec![vec![8.0, 14.0], vec![4.0, 12.0]])));
+    assert_eq!(evaluate("B * A", &args), Err("Incompatible shapes 2x3 matrix and 2x2 matrix in 'B * A' at 2..3".to_string()));
+    assert_eq!(evaluate("B * v", &args), Err("Incompatible shapes 2x3 matrix and list of 2 in 'B * v' at 2..3".to_string()));
+}
+
+#[test]
+fn test_element_wise() {
+    let args = matrix_args();
+    assert_eq!(evaluate("A + [[1, 1], [1, 1]]", &args), Ok(matrix(vec![vec![5.0, 8.0], vec![3.0, 7.0]])));
+    assert_eq!(evaluate("A / 2 - 1", &args), Ok(matrix(vec![vec![1.0, 2.5], vec![0.0, 2.0]])));
+    assert_eq!(evaluate("sqrt(A * 4 / A)", &args), Ok(matrix(vec![vec![2.0, 2.0], vec![2.0, 2.0]])));
+    assert_eq!(evaluate("sum(A) + max(B)", &args), Ok(Value::Number(25.0)));
+    assert_eq!(evaluate("A + B", &args), Err("Incompatible shapes 2x2 matrix and 2x3 matrix in 'A + B' at 2..3".to_string()));
+    assert_eq!(evaluate("A + v", &args), Err("Incompatible shapes 2x2 matrix and list of 2 in 'A + v' at 2..3".to_string()));
+    assert_eq!(evaluate("[[1, 2], [3]]", &args), Err("Incompatible shapes list of 2 and list of 1 in '[[1, 2], [3]]' at 0..13".to_string()));
+}
+
+#[test]
+fn test_matrix_functions() {
+    let args = matrix_args();
+    assert_eq!(evaluate("transpose(B)", &args), Ok(matrix(vec![vec![1.0, 4.0], vec![2.0, 5.0], vec![3.0, 6.0]])));
+    assert_eq!(evaluate("det(A)", &args), Ok(Value::Number(10.0)));
+    assert_eq!(evaluate("det([[0, 1], [1, 0]])", &args), Ok(Value::Number(-1.0)));
+    assert_eq!(evaluate("det([[1, 2], [2, 4]])", &args), Ok(Value::Number(0.0)));
+    assert_close(evaluate("inv(A)", &args), matrix(vec![vec![0.6, -0.7], vec![-0.2, 0.4]]));
+    assert_close(evaluate("inv(A) * A", &args), matrix(vec![vec![1.0, 0.0], vec![0.0, 1.0]]));
+    assert_eq!(evaluate("let M = [[2, 0], [0, 4]] in det(inv(M))", &args), Ok(Value::Number(0.125)));
+    assert_eq!(parse_string("det(transpose(A))".to_string()).unwrap().to_string(), "det(transpose(A))");
+}
+
+#[test]
+fn test_matrix_errors() {
+    let args = matrix_args();
+    assert_eq!(evaluate("det(B)", &args), Err("Expected a square matrix, got a 2x3 matrix in 'det(B)' at 0..6".to_string()));
+    assert_eq!(evaluate("inv([[1, 2], [2, 4]])", &args), Err("Arithmetic error (Matrix is singular)".to_string()));
+    assert_eq!(evaluate("det(v)", &args), Err("Expected a matrix, got a list".to_string()));
+    assert_eq!(parse_string("inv(A, B)".to_string()).err().unwrap().to_string(), "Wrong number of arguments for 'inv' (expected 1, got 2)");
+}
+
+#[test]
+fn test_error_positions() {
+    let args = matrix_args();
+    let error = parse_string("2 * (1 + det(B))".to_string()).unwrap().evaluate_value(&args).err().unwrap();
+    assert_eq!(error.downcast::<NotSquare>().unwrap().span, Some(9..15));
+    let error = parse_string("let M = B in sum(M * M)".to_string()).unwrap().evaluate_value(&args).err().unwrap();
+    assert_eq!(error.downcast::<ShapeMismatch>().unwrap().span, Some(19..20));
+    assert_eq!(evaluate("[1, 2] + max([[1], [2, 3]])", &args), Err("Incompatible shapes list of 1 and list of 2 in '[[1], [2, 3]]' at 13..26".to_string()));
+}
+
+#[test]
+fn test_exact_inverse() {
+    let exp = parse_string_as::<Rational>("inv([[2, 1], [1, 1]]) * [[2, 1], [1, 1]]".to_string()).unwrap();
+    let identity = Matrix::new(2, 2, [1, 0, 0, 1].map(|value| Rational::new(value, 1).unwrap()).to_vec()).unwrap();
+    assert_eq!(exp.evaluate_value(&ExpressionArgs::empty()).unwrap(), Value::Matrix(identity));
+}
@@ -0,0 +1,83 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use expression_parser::bytecode::Program;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::number::Number;
+use expression_parser::parser::{parse_string, parse_string_as};
+
+/// Fixed-point number with four decimal places.
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Fixed(i64);
+
+const SCALE: i64 = 10_000;
+
+impl Display for Fixed {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result { write!(f, "{}.{:04}", self.0 / SCALE, (self.0 % SCALE).abs()) }
+}
+
+impl Number for Fixed {
+    fn parse_literal(literal: &str) -> Result<Self, Box<dyn Error>> {
+        let (whole, fraction) = literal.split_once('.').unwrap_or((literal, ""));
+        let fraction = format!("{:0<4}", fraction);
+        Ok(Fixed(whole.parse::<i64>()? * SCALE + fraction[..4].parse::<i64>()?))
+    }
+    fn add(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(Fixed(self.0 + other.0)) }
+    fn subtract(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(Fixed(self.0 - other.0)) }
+    fn multiply(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> { Ok(Fixed(self.0 * other.0 / SCALE)) }
+    fn divide(&self, other: &Self, _settings: &ExpressionSettings) -> Result<Self, Box<dyn Error>> {
+        if other.0 == 0 {
+            return Err(Box::from("Fixed-point division by zero"));
+        }
+        Ok(Fixed(self.0 * SCALE / other.0))
+    }
+    fn is_zero(&self, _delta: f64) -> bool { self.0 == 0 }
+    fn to_index(&self) -> Option<usize> {
+        match self.0 % SCALE {
+            0 => usize::try_from(self.0 / SCALE).ok(),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_f32() {
+    let exp = parse_string_as::<f32>("1.5 * x".to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 4.0f32);
+    assert_eq!(exp.evaluate(&args).unwrap(), 6.0f32);
+}
+
+#[test]
+fn test_custom_number() {
+    let exp = parse_string_as::<Fixed>("1.5 * 2.25 - 0.125".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "1.5000 * 2.2500 - 0.1250");
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).unwrap(), Fixed(32500));
+}
+
+#[test]
+fn test_custom_number_error() {
+    let exp = parse_string_as::<Fixed>("x / 0".to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), Fixed(SCALE));
+    assert!(!exp.can_evaluate(&args));
+    assert_eq!(exp.evaluate(&args).err().unwrap().to_string(), "Fixed-point division by zero");
+}
+
+#[test]
+fn test_custom_number_bytecode() {
+    let exp = parse_string_as::<Fixed>("x * 3 + 0.5".to_string()).unwrap();
+    let program = Program::compile(exp.as_ref()).unwrap();
+    assert_eq!(program.evaluate(&[Fixed(2 * SCALE)], &ExpressionSettings::default()).unwrap(), Fixed(65000));
+}
+
+#[test]
+fn test_missing_operand() {
+    let exp = parse_string("1 +".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Addition is missing its right side");
+}
+
+#[test]
+fn test_undefined_variable() {
+    let exp = parse_string("x * 2".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Unknown variable 'x'");
+}
@@ -0,0 +1,58 @@
+use expression_parser::enums::NonFinitePolicy;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::parser::{parse_string, parse_string_as};
+
+fn evaluate(input: &str, non_finite: NonFinitePolicy) -> Result<f64, String> {
+    let mut args = ExpressionArgs::empty();
+    args.settings = ExpressionSettings { non_finite, ..ExpressionSettings::default() };
+    parse_string(input.to_string()).unwrap().evaluate(&args).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_policy_propagate() {
+    assert_eq!(evaluate("1 / 0", NonFinitePolicy::Propagate), Ok(f64::INFINITY));
+    assert!(evaluate("sqrt(-1)", NonFinitePolicy::Propagate).unwrap().is_nan());
+    assert_eq!(evaluate("ln(0)", NonFinitePolicy::Propagate), Ok(f64::NEG_INFINITY));
+}
+
+#[test]
+fn test_policy_error() {
+    let error = |input: &str| evaluate(input, NonFinitePolicy::Error).unwrap_err();
+    assert_eq!(error("1 / 0"), "Arithmetic error (Division by zero)");
+    assert_eq!(error("0 / 0"), "Arithmetic error (Division by zero)");
+    assert_eq!(error("5 % 0"), "Arithmetic error (Division by zero)");
+    assert_eq!(error("sqrt(-1)"), "Arithmetic error (Square root of a negative number)");
+    assert_eq!(error("ln(0)"), "Arithmetic error (Logarithm of zero)");
+    assert_eq!(error("ln(-2)"), "Arithmetic error (Logarithm of a negative number)");
+    assert_eq!(error("10 ^ 300 * 10 ^ 300"), "Arithmetic error (Overflow to infinity)");
+    assert_eq!(error("10 ^ 400"), "Arithmetic error (Overflow to infinity)");
+    assert_eq!(evaluate("sqrt(4) + 1 / 2", NonFinitePolicy::Error), Ok(2.5));
+}
+
+#[test]
+fn test_policy_substitute() {
+    let substitute = NonFinitePolicy::Substitute(0.0);
+    assert_eq!(evaluate("1 / 0 + 1", substitute), Ok(1.0));
+    assert_eq!(evaluate("sqrt(-4) + ln(0)", substitute), Ok(0.0));
+    assert_eq!(evaluate("10 ^ 300 * 10 ^ 300", NonFinitePolicy::Substitute(f64::MAX)), Ok(f64::MAX));
+}
+
+#[test]
+fn test_policy_per_evaluation() {
+    let exp = parse_string("1 / x".to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 0.0);
+    assert_eq!(exp.evaluate(&args).unwrap(), f64::INFINITY);
+    args.settings.non_finite = NonFinitePolicy::Error;
+    assert!(exp.evaluate(&args).is_err());
+    args.settings.non_finite = NonFinitePolicy::Substitute(-1.0);
+    assert_eq!(exp.evaluate(&args).unwrap(), -1.0);
+}
+
+#[test]
+fn test_policy_f32() {
+    let mut args = ExpressionArgs::empty();
+    args.settings.non_finite = NonFinitePolicy::Error;
+    let result = parse_string_as::<f32>("10 ^ 30 * 10 ^ 30".to_string()).unwrap().evaluate(&args);
+    assert_eq!(result.unwrap_err().to_string(), "Arithmetic error (Overflow to infinity)");
+}
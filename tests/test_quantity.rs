@@ -0,0 +1,58 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string_as;
+use expression_parser::quantity::Quantity;
+
+fn evaluate(input: &str, args: &ExpressionArgs<Quantity>) -> Result<Quantity, String> {
+    parse_string_as::<Quantity>(input.to_string()).unwrap().evaluate(args).map_err(|e| e.to_string())
+}
+
+#[test]
+fn test_quantity_derived_unit() {
+    let exp = parse_string_as::<Quantity>("5 m / 2 s".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "5 m / 2 s");
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).unwrap().to_string(), "2.5 m/s");
+    assert_eq!(evaluate("3 m * 4 m", &ExpressionArgs::empty()).unwrap().to_string(), "12 m^2");
+    assert_eq!(evaluate("2 kg * 9.81 m/s^2", &ExpressionArgs::empty()).unwrap().to_string(), "19.62 kg*m/s^2");
+    assert_eq!(evaluate("10 m / 5 m", &ExpressionArgs::empty()).unwrap().to_string(), "2");
+}
+
+#[test]
+fn test_quantity_addition() {
+    assert_eq!(evaluate("1 km + 300 m", &ExpressionArgs::empty()).unwrap().to_string(), "1300 m");
+    assert_eq!(evaluate("2 km + 3 km", &ExpressionArgs::empty()).unwrap().to_string(), "5 km");
+}
+
+#[test]
+fn test_quantity_dimension_mismatch() {
+    let error = evaluate("2 * (5 m + 2 s)", &ExpressionArgs::empty()).err().unwrap();
+    assert_eq!(error, "Incompatible dimensions m and s in '5 m + 2 s'");
+    let error = evaluate("exp(2 m)", &ExpressionArgs::empty()).err().unwrap();
+    assert_eq!(error, "Incompatible dimensions m and 1 in 'exp(2 m)'");
+}
+
+#[test]
+fn test_quantity_conversion() {
+    let exp = parse_string_as::<Quantity>("100 m / 10 s to km/h".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "100 m / 10 s to km / h");
+    let result = exp.evaluate(&ExpressionArgs::empty()).unwrap();
+    assert!((result.value() - 36.0).abs() < 1e-9);
+    assert_eq!(result.unit(), "km/h");
+    assert_eq!(evaluate("1 h to min", &ExpressionArgs::empty()).unwrap().to_string(), "60 min");
+    assert_eq!(evaluate("5 m to s", &ExpressionArgs::empty()).err().unwrap(), "Incompatible dimensions m and s in '5 m to s'");
+}
+
+#[test]
+fn test_quantity_variables() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("distance".to_string(), Quantity::new(42.195, "km").unwrap());
+    args.variables.insert("time".to_string(), Quantity::new(2.0, "h").unwrap());
+    args.variables.insert("x".to_string(), Quantity::dimensionless(3.0));
+    assert_eq!(evaluate("distance / time", &args).unwrap().to_string(), "21.0975 km/h");
+    assert_eq!(evaluate("x m + 1 m", &args).unwrap().to_string(), "4 m");
+}
+
+#[test]
+fn test_quantity_power() {
+    assert_eq!(evaluate("sqrt(16 m^2)", &ExpressionArgs::empty()).unwrap().to_string(), "4 m");
+    assert_eq!(evaluate("(2 m) ^ 0.5", &ExpressionArgs::empty()).err().unwrap(), "Arithmetic error (Power would give a fractional unit)");
+}
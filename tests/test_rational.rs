@@ -0,0 +1,75 @@
+use std::cmp::Ordering;
+use expression_parser::expression::{ExpressionArgs, ExpressionSettings};
+use expression_parser::number::Number;
+use expression_parser::parser::parse_string_as;
+use expression_parser::rational::Rational;
+
+fn evaluate(input: &str) -> Rational {
+    parse_string_as::<Rational>(input.to_string()).unwrap().evaluate(&ExpressionArgs::empty()).unwrap()
+}
+
+#[test]
+fn test_rational_exact_sum() {
+    assert_eq!(evaluate("0.1 + 0.2"), evaluate("0.3"));
+    assert_eq!(evaluate("0.1 + 0.2").to_string(), "3/10");
+}
+
+#[test]
+fn test_rational_literal() {
+    let value = Rational::parse_literal("-21.25").unwrap();
+    assert_eq!((value.numerator(), value.denominator()), (-85, 4));
+    assert_eq!(Rational::parse_literal("1.").unwrap(), Rational::from_integer(1));
+    assert!(Rational::parse_literal("-").is_err());
+}
+
+#[test]
+fn test_rational_reduced() {
+    assert_eq!(evaluate("1 / 3 * 6"), Rational::from_integer(2));
+    assert_eq!(evaluate("-2 / 4").to_string(), "-1/2");
+    assert_eq!(Rational::new(6, -8).unwrap().to_string(), "-3/4");
+}
+
+#[test]
+fn test_rational_print_and_parse() {
+    let reparse = |input: &str| parse_string_as::<Rational>(parse_string_as::<Rational>(input.to_string()).unwrap().to_string()).unwrap();
+    assert_eq!(parse_string_as::<Rational>("1 / 0.5".to_string()).unwrap().to_string(), "1 / (1/2)");
+    assert_eq!(reparse("1 / 0.5").evaluate(&ExpressionArgs::empty()).unwrap(), Rational::from_integer(2));
+    assert_eq!(reparse("2 ^ 0.5").to_string(), "2 ^ (1 / 2)");
+    assert_eq!(reparse("0.75 - 2").to_string(), "(3 / 4) - 2");
+}
+
+#[test]
+fn test_rational_division_by_zero() {
+    let exp = parse_string_as::<Rational>("1 / 0".to_string()).unwrap();
+    assert!(!exp.can_evaluate(&ExpressionArgs::empty()));
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Arithmetic error (Division by zero)");
+}
+
+#[test]
+fn test_rational_overflow() {
+    let exp = parse_string_as::<Rational>("x * x".to_string()).unwrap();
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), Rational::from_integer(i128::MAX / 2));
+    assert_eq!(exp.evaluate(&args).err().unwrap().to_string(), "Arithmetic error (Rational overflow)");
+}
+
+#[test]
+fn test_rational_to_decimal() {
+    assert_eq!(evaluate("2 / 3").to_decimal_string(4), "0.6667");
+    assert_eq!(evaluate("-1 / 8").to_decimal_string(2), "-0.13");
+    assert_eq!(evaluate("19.995").to_decimal_string(2), "20.00");
+    assert_eq!(evaluate("-0.001").to_decimal_string(2), "0.00");
+    assert_eq!(evaluate("7 / 2").to_decimal_string(0), "4");
+    assert_eq!(evaluate("21.25").to_f64(), 21.25);
+}
+
+#[test]
+fn test_rational_comparison_extremes() {
+    let settings = ExpressionSettings::default();
+    let larger = Rational::new(i128::MAX, 3).unwrap();
+    let smaller = Rational::new(i128::MAX - 2, 3).unwrap();
+    assert_eq!(larger.compare(&smaller, &settings).unwrap(), Ordering::Greater);
+    assert_eq!(smaller.compare(&larger, &settings).unwrap(), Ordering::Less);
+    assert_eq!(Rational::new(-i128::MAX, 7).unwrap().compare(&Rational::new(i128::MAX, 5).unwrap(), &settings).unwrap(), Ordering::Less);
+    assert!(larger.is_equal(&Rational::new(i128::MAX, 3).unwrap(), &settings).unwrap());
+}
@@ -0,0 +1,64 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::parse_string;
+use expression_parser::value::Value;
+
+fn evaluate(input: &str, args: &ExpressionArgs) -> Result<Value, String> {
+    parse_string(input.to_string()).map_err(|e| e.to_string())?.evaluate_value(args).map_err(|e| e.to_string())
+}
+
+fn text(value: &str) -> Value {
+    Value::String(value.to_string())
+}
+
+fn text_args() -> ExpressionArgs {
+    let mut args = ExpressionArgs::empty();
+    args.strings.insert("status".to_string(), "open".to_string());
+    args.variables.insert("x".to_string(), 2.5);
+    args
+}
+
+#[test]
+fn test_string_literal() {
+    let args = ExpressionArgs::empty();
+    assert_eq!(evaluate("\"hello, world\"", &args), Ok(text("hello, world")));
+    assert_eq!(evaluate("\"\"", &args), Ok(text("")));
+    assert_eq!(evaluate("\"say \\\"hi\\\"\\n\\tand \\\\ bye\"", &args), Ok(text("say \"hi\"\n\tand \\ bye")));
+    let exp = parse_string("concat(\"a\\\"b\", \"(\")".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "concat(\"a\\\"b\", \"(\")");
+    assert_eq!(text("a\"b").to_string(), "a\"b");
+}
+
+#[test]
+fn test_text_functions() {
+    let args = text_args();
+    assert_eq!(evaluate("concat(\"x = \", x, \", \", status)", &args), Ok(text("x = 2.5, open")));
+    assert_eq!(evaluate("concat()", &args), Ok(text("")));
+    assert_eq!(evaluate("upper(status)", &args), Ok(text("OPEN")));
+    assert_eq!(evaluate("lower(\"MiXeD\")", &args), Ok(text("mixed")));
+    assert_eq!(evaluate("len(\"zażółć\")", &args), Ok(Value::Number(6.0)));
+    assert_eq!(evaluate("len(status) + len([1, 2])", &args), Ok(Value::Number(6.0)));
+    assert_eq!(evaluate("if(x > 2, \"high\", \"low\")", &args), Ok(text("high")));
+    assert_eq!(evaluate("let label = upper(status) in concat(label, \"!\")", &args), Ok(text("OPEN!")));
+}
+
+#[test]
+fn test_string_comparison() {
+    let args = text_args();
+    assert_eq!(evaluate("status == \"open\"", &args), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("status != \"closed\" and x < 3", &args), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("\"apple\" < \"banana\"", &args), Ok(Value::Boolean(true)));
+    assert_eq!(evaluate("upper(status) >= status", &args), Ok(Value::Boolean(false)));
+    assert_eq!(evaluate("status == 1", &args), Err("Expected a string, got a number".to_string()));
+    assert_eq!(evaluate("x == status", &args), Err("Expected a number, got a string".to_string()));
+}
+
+#[test]
+fn test_string_errors() {
+    let args = text_args();
+    assert_eq!(evaluate("\"a\" + 1", &args), Err("Expected a number, got a string".to_string()));
+    assert_eq!(evaluate("upper(x)", &args), Err("Expected a string, got a number".to_string()));
+    assert_eq!(evaluate("concat(\"a\", [1])", &args), Err("Expected a string, got a list".to_string()));
+    assert_eq!(evaluate("\"open", &args), Err("Parsing buffer error (Unclosed string)".to_string()));
+    assert!(evaluate("\"a\\q\"", &args).unwrap_err().contains("Unknown escape sequence"));
+    assert_eq!(evaluate("upper(\"a\", \"b\")", &args), Err("Wrong number of arguments for 'upper' (expected 1, got 2)".to_string()));
+}
@@ -0,0 +1,40 @@
+use std::sync::Arc;
+use std::thread;
+use expression_parser::expression::{Expression, ExpressionArgs};
+use expression_parser::parser::parse_string;
+
+fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+#[test]
+fn test_send_sync() {
+    assert_send_sync::<dyn Expression>();
+    assert_send_sync::<ExpressionArgs>();
+}
+
+#[test]
+fn test_shared_expression() {
+    let exp: Arc<dyn Expression> = Arc::from(parse_string("x * 2 + 1".to_string()).unwrap());
+    let handles: Vec<_> = (0..4)
+        .map(|i| {
+            let exp = Arc::clone(&exp);
+            thread::spawn(move || {
+                let mut args = ExpressionArgs::empty();
+                args.variables.insert("x".to_string(), i as f64);
+                exp.evaluate(&args).unwrap()
+            })
+        })
+        .collect();
+    let results: Vec<f64> = handles.into_iter().map(|handle| handle.join().unwrap()).collect();
+    assert_eq!(results, [1.0, 3.0, 5.0, 7.0]);
+}
+
+#[test]
+fn test_shared_args() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), 3.0);
+    args.functions.insert("double".to_string(), Arc::new(|value| value * 2.0));
+    let args = Arc::new(args);
+    let shared = Arc::clone(&args);
+    let result = thread::spawn(move || shared.functions["double"](shared.variables["x"])).join().unwrap();
+    assert_eq!(result, 6.0);
+}
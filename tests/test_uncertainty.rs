@@ -0,0 +1,55 @@
+use expression_parser::expression::ExpressionArgs;
+use expression_parser::parser::{parse_string, parse_string_as};
+use expression_parser::uncertainty::Measurement;
+
+fn evaluate(input: &str, args: &ExpressionArgs<Measurement>) -> Measurement {
+    parse_string_as::<Measurement>(input.to_string()).unwrap().evaluate(args).unwrap()
+}
+
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-12, "expected {}, got {}", expected, actual);
+}
+
+#[test]
+fn test_uncertainty_literal() {
+    let exp = parse_string_as::<Measurement>("12.3 ± 0.2".to_string()).unwrap();
+    assert_eq!(exp.to_string(), "12.3 ± 0.2");
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).unwrap().to_string(), "12.3 ± 0.2");
+}
+
+#[test]
+fn test_uncertainty_sum_in_quadrature() {
+    let result = evaluate("10 ± 0.3 + 5 ± 0.4", &ExpressionArgs::empty());
+    assert_eq!(result.value(), 15.0);
+    assert_close(result.uncertainty(), 0.5);
+}
+
+#[test]
+fn test_uncertainty_product_and_quotient() {
+    let product = evaluate("(2 ± 0.02) * 3 ± 0.06", &ExpressionArgs::empty());
+    assert_close(product.uncertainty(), 6.0 * (0.01f64.powi(2) + 0.02f64.powi(2)).sqrt());
+    let quotient = evaluate("1 / 4 ± 0.1", &ExpressionArgs::empty());
+    assert_close(quotient.uncertainty(), 0.1 / 16.0);
+}
+
+#[test]
+fn test_uncertainty_correlated() {
+    let mut args = ExpressionArgs::empty();
+    args.variables.insert("x".to_string(), Measurement::new(3.0, 0.1).unwrap());
+    assert_eq!(evaluate("x - x", &args).to_string(), "0 ± 0");
+    assert_close(evaluate("x + x", &args).uncertainty(), 0.2);
+}
+
+#[test]
+fn test_uncertainty_power_and_functions() {
+    assert_close(evaluate("(3 ± 0.1) ^ 2", &ExpressionArgs::empty()).uncertainty(), 0.6);
+    assert_close(evaluate("sqrt(4 ± 0.4)", &ExpressionArgs::empty()).uncertainty(), 0.1);
+    assert_close(evaluate("sin(0 ± 0.01)", &ExpressionArgs::empty()).uncertainty(), 0.01);
+    assert_close(evaluate("ln(2 ± 0.2)", &ExpressionArgs::empty()).uncertainty(), 0.1);
+}
+
+#[test]
+fn test_uncertainty_unsupported() {
+    let exp = parse_string("1 ± 0.1".to_string()).unwrap();
+    assert_eq!(exp.evaluate(&ExpressionArgs::empty()).err().unwrap().to_string(), "Operation '±' is not supported by this number type");
+}